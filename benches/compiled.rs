@@ -0,0 +1,143 @@
+//! Compares runs of FSM with runs of compiled FSM and byte automaton
+//! (run with "cargo bench")
+
+use std::time::{Duration, Instant};
+use fsm_rust_jb::{map, FSM, Transition, CompiledFSM, ByteDFA};
+use fsm_rust_jb::types::StreamData;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Space,
+    Word,
+    Number
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    Word,
+    Number
+}
+
+fn is_letter(ch: char) -> bool {
+    ch.is_ascii_alphabetic()
+}
+
+fn is_digit(ch: char) -> bool {
+    ch.is_ascii_digit()
+}
+
+fn is_space(ch: char) -> bool {
+    ch == ' ' || ch == '\n'
+}
+
+fn setup_fsm() -> FSM<State, Effect> {
+    FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Word)),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Number)),
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), None),
+                Transition::new(State::Word, Some(is_digit), None),
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), None),
+                Transition::new(State::Space, Some(is_space), None)
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+/// Measures average time of run per character
+/// - name: name of runner,
+/// - length: number of characters of input,
+/// - run: single run
+fn measure(name: &str, length: usize, mut run: impl FnMut()) {
+    const ROUNDS: u32 = 20;
+
+    run();
+
+    let start = Instant::now();
+
+    for _ in 0..ROUNDS {
+        run();
+    }
+
+    let elapsed: Duration = start.elapsed() / ROUNDS;
+
+    println!(
+        "{:<32} {:>10.2?} per run, {:>6.2} ns per character",
+        name,
+        elapsed,
+        elapsed.as_nanos() as f64 / length as f64
+    );
+}
+
+fn main() {
+    let string = "lorem ipsum 42 dolor2 sit 7 amet\n".repeat(30_000);
+    let length = string.chars().count();
+
+    let fsm = setup_fsm();
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+    let byte_dfa = ByteDFA::from_chars(&setup_fsm()).unwrap();
+
+    measure("FSM", length, || {
+        let mut words = 0;
+        let mut counter = |effect: Effect, _: StreamData| {
+            if effect == Effect::Word {
+                words += 1;
+            }
+        };
+
+        assert!(fsm.proceed(&string, Some(&mut counter)).is_ok());
+        assert_eq!(words, 5 * 30_000);
+    });
+
+    measure("CompiledFSM", length, || {
+        let mut words = 0;
+        let mut counter = |effect: Effect, _: StreamData| {
+            if effect == Effect::Word {
+                words += 1;
+            }
+        };
+
+        assert!(compiled.proceed(&string, Some(&mut counter)).is_ok());
+        assert_eq!(words, 5 * 30_000);
+    });
+
+    measure("ByteDFA", length, || {
+        let mut words = 0;
+        let mut counter = |effect: Effect, _: StreamData<u8>| {
+            if effect == Effect::Word {
+                words += 1;
+            }
+        };
+
+        assert!(byte_dfa.proceed(string.as_bytes(), Some(&mut counter)).is_ok());
+        assert_eq!(words, 5 * 30_000);
+    });
+
+    // parallel runs are faster only with as many cores as threads
+    for threads in [4, 8].iter() {
+        measure(&format!("CompiledFSM ({} threads)", threads), length, || {
+            assert!(compiled.proceed_parallel(&string, *threads, None).is_ok());
+        });
+
+        measure(&format!("CompiledFSM ({} threads, effects)", threads), length, || {
+            let mut words = 0;
+            let mut counter = |effect: Effect, _: StreamData| {
+                if effect == Effect::Word {
+                    words += 1;
+                }
+            };
+
+            assert!(compiled.proceed_parallel(&string, *threads, Some(&mut counter)).is_ok());
+            assert_eq!(words, 5 * 30_000);
+        });
+    }
+}
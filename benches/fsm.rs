@@ -0,0 +1,137 @@
+//! Measures throughput of FSM runs over flat and nested states
+//! (run with "cargo bench --bench fsm")
+
+use std::time::{Duration, Instant};
+use fsm_rust_jb::{map, FSM, Transition};
+use fsm_rust_jb::types::StreamData;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Text,
+    Space,
+    Word,
+    Number
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    Word,
+    Number
+}
+
+fn is_letter(ch: char) -> bool {
+    ch.is_ascii_alphabetic()
+}
+
+fn is_digit(ch: char) -> bool {
+    ch.is_ascii_digit()
+}
+
+fn is_space(ch: char) -> bool {
+    ch == ' ' || ch == '\n'
+}
+
+fn setup_flat_fsm() -> FSM<State, Effect> {
+    FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Word)),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Number)),
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), None),
+                Transition::new(State::Word, Some(is_digit), None),
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), None),
+                Transition::new(State::Space, Some(is_space), None)
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+/// The same machine with states nested into composite state
+/// that holds their common transition by space
+fn setup_nested_fsm() -> FSM<State, Effect> {
+    let mut fsm = FSM::new(
+        State::Text,
+        map!(
+            State::Text => vec![
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Word)),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Number))
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), None),
+                Transition::new(State::Word, Some(is_digit), None)
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), None)
+            ]
+        ),
+        None
+    ).unwrap();
+
+    for state in [State::Space, State::Word, State::Number].iter() {
+        fsm.set_parent(*state, State::Text).unwrap();
+    }
+
+    fsm.set_initial_child(State::Text, State::Space).unwrap();
+
+    fsm
+}
+
+/// Measures average time of run per character
+/// - name: name of run,
+/// - length: number of characters of input,
+/// - run: single run
+fn measure(name: &str, length: usize, mut run: impl FnMut()) {
+    const ROUNDS: u32 = 20;
+
+    run();
+
+    let start = Instant::now();
+
+    for _ in 0..ROUNDS {
+        run();
+    }
+
+    let elapsed: Duration = start.elapsed() / ROUNDS;
+
+    println!(
+        "{:<32} {:>10.2?} per run, {:>6.2} ns per character, {:>8.2} MB/s",
+        name,
+        elapsed,
+        elapsed.as_nanos() as f64 / length as f64,
+        length as f64 / elapsed.as_secs_f64() / 1e6
+    );
+}
+
+fn main() {
+    let string = "lorem ipsum 42 dolor2 sit 7 amet\n".repeat(30_000);
+    let length = string.chars().count();
+
+    for (name, fsm) in [("flat", setup_flat_fsm()), ("nested", setup_nested_fsm())].iter() {
+        measure(&format!("FSM::proceed ({})", name), length, || {
+            assert!(fsm.proceed(&string, None).is_ok());
+        });
+
+        measure(&format!("FSM::proceed ({}, effects)", name), length, || {
+            let mut words = 0;
+            let mut counter = |effect: Effect, _: StreamData| {
+                if effect == Effect::Word {
+                    words += 1;
+                }
+            };
+
+            assert!(fsm.proceed(&string, Some(&mut counter)).is_ok());
+            assert_eq!(words, 5 * 30_000);
+        });
+    }
+}
@@ -0,0 +1,345 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::map::Map;
+use crate::types::{Effector, StreamData, Transition};
+use crate::fsm::{FSM, FSMError, EMPTY_STRING, dispatch_all};
+use crate::compiled::{Layout, CompileError, classify};
+
+/// Number of bytes
+const BYTES: usize = 256;
+
+/// Range of continuation bytes of UTF-8 sequences
+const CONTINUATION: core::ops::RangeInclusive<u8> = 0x80..=0xBF;
+
+/// Machine that runs directly over bytes by dense tables:
+/// bytes are split into classes (bytes that behave the same in every node)
+/// and every node has single move for every class.
+/// Nodes are states of source FSM and (for FSM over characters)
+/// positions inside UTF-8 sequences of characters.
+/// Effects get byte position and byte that completes transition
+/// (i.e. last byte of character for FSM over characters)
+pub struct ByteDFA<State, Effect, Error = ()>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    /// Indexed states with their moves (states are the first nodes)
+    layout: Layout<State, Effect>,
+    /// Number of nodes
+    node_count: usize,
+    /// Class of every byte
+    classes: Vec<usize>,
+    /// Number of classes
+    class_count: usize,
+    /// Edge of every node for every class (row per node)
+    table: Vec<Option<Edge>>,
+    error: PhantomData<fn() -> Error>
+}
+
+/// Edge of node for byte
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+enum Edge {
+    /// Byte continues character (node inside UTF-8 sequence)
+    Node(usize),
+    /// Byte completes transition of state (index of move)
+    Move(usize)
+}
+
+/// Builder of nodes inside UTF-8 sequences (equal nodes are shared)
+struct Nodes {
+    /// Index of the first node (after states)
+    offset: usize,
+    /// Edges of every node for continuation bytes
+    rows: Vec<Vec<Option<Edge>>>,
+    /// Indices of nodes by their edges
+    indices: Map<Vec<Option<Edge>>, usize>
+}
+
+impl<State, Effect, Error> ByteDFA<State, Effect, Error>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    /// Compiles FSM over bytes
+    /// - fsm: FSM without guards, registers and history pseudo-states
+    pub fn new(fsm: &FSM<State, Effect, Error, (), u8>) -> Result<Self, CompileError<State>> {
+        let layout = Layout::new(fsm)?;
+
+        let rows: Vec<Vec<Option<Edge>>> = (0..layout.states.len())
+            .map(|state| {
+                (0..BYTES)
+                    .map(|byte| layout.find_move(fsm, state, byte as u8).map(Edge::Move))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self::with_rows(layout, rows))
+    }
+
+    /// Compiles FSM over characters into automaton over bytes of UTF-8.
+    /// Every character is matched once while compiling
+    /// (conditions must be pure functions), so it takes a while.
+    /// Invalid UTF-8 is rejected at the first byte that can't continue
+    /// - fsm: FSM without guards, registers and history pseudo-states
+    pub fn from_chars(fsm: &FSM<State, Effect, Error>) -> Result<Self, CompileError<State>> {
+        let layout = Layout::new(fsm)?;
+        let state_count = layout.states.len();
+
+        let mut nodes = Nodes {
+            offset: state_count,
+            rows: Vec::new(),
+            indices: Map::new()
+        };
+
+        let mut rows = Vec::with_capacity(state_count);
+
+        for state in 0..state_count {
+            // transitions of state and then of its composite states
+            let transitions: Vec<&Transition<State, Effect>> = if layout.is_known[state] {
+                fsm.hierarchy().ancestors(layout.states[state])
+                    .filter_map(|state| fsm.transitions(state))
+                    .flatten()
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let offset = layout.offsets[state];
+            let find_move = |code: u32| -> Option<Edge> {
+                let ch = char::from_u32(code)?;
+
+                transitions.iter()
+                    .position(|transition| transition.transit(ch).0.is_some())
+                    .map(|trans_id| Edge::Move(offset + trans_id))
+            };
+
+            rows.push(nodes.lower(find_move));
+        }
+
+        rows.extend(nodes.rows.into_iter().map(|continuations| {
+            let mut row = vec![None; BYTES];
+
+            for (byte, edge) in CONTINUATION.zip(continuations) {
+                row[byte as usize] = edge;
+            }
+
+            row
+        }));
+
+        Ok(Self::with_rows(layout, rows))
+    }
+
+    /// Returns number of classes of bytes
+    pub fn class_count(&self) -> usize {
+        self.class_count
+    }
+
+    /// Returns number of nodes (states and positions inside UTF-8 sequences)
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Runs bytes through machine (see "FSM::proceed_iter").
+    /// Run goes the same way as run of source FSM, except that
+    /// state which effector goes to must belong to FSM.
+    /// Input ending inside UTF-8 sequence is rejected at position after last byte
+    /// - bytes: runnable bytes,
+    /// - effector: module that mutates some data by effects
+    pub fn proceed<'a>(
+        &self,
+        bytes: &'a [u8],
+        mut effector: Option<&'a mut dyn Effector<Effect, State, Error, u8>>
+    ) -> Result<(), FSMError<'a, State, Error, u8>> {
+        let layout = &self.layout;
+        let mut state = layout.start;
+
+        let control = dispatch_all(
+            &mut effector,
+            layout.start_effects.iter(),
+            StreamData {
+                string: &EMPTY_STRING,
+                index: 0,
+                character: None
+            },
+            None
+        );
+
+        if !layout.control(control, &mut state)? {
+            return Ok(());
+        }
+
+        let mut node = state;
+
+        for (index, byte) in bytes.iter().enumerate() {
+            let input_data = StreamData {
+                string: &EMPTY_STRING,
+                index,
+                character: Some(*byte)
+            };
+
+            match self.table[node * self.class_count + self.classes[*byte as usize]] {
+                Some(Edge::Node(next)) => node = next,
+                Some(Edge::Move(move_id)) => {
+                    let step = &layout.moves[move_id];
+                    state = step.to;
+
+                    let control = dispatch_all(
+                        &mut effector,
+                        step.effects.iter(),
+                        input_data,
+                        None
+                    );
+
+                    if !layout.control(control, &mut state)? {
+                        return Ok(());
+                    }
+
+                    node = state;
+                },
+                None if node == state && !layout.is_known[state] => {
+                    return Err(FSMError::StateDoesNotExist(layout.states[state]));
+                },
+                None => return Err(FSMError::NoValidTransition {
+                    from: layout.states[state],
+                    input_data
+                })
+            }
+        }
+
+        let end_data = StreamData {
+            string: &EMPTY_STRING,
+            index: bytes.len(),
+            character: None
+        };
+
+        if node != state {
+            return Err(FSMError::NoValidTransition {
+                from: layout.states[state],
+                input_data: end_data
+            });
+        }
+
+        layout.end(&mut effector, state, end_data)
+    }
+
+    /// Creates machine from edges of every node for every byte
+    /// - layout: indexed states with their moves,
+    /// - rows: edges of states and then of other nodes
+    fn with_rows(layout: Layout<State, Effect>, rows: Vec<Vec<Option<Edge>>>) -> Self {
+        let (classes, class_count, table) = classify(
+            BYTES,
+            rows.len(),
+            |node, byte| rows[node][byte]
+        );
+
+        Self {
+            layout,
+            node_count: rows.len(),
+            classes,
+            class_count,
+            table,
+            error: PhantomData
+        }
+    }
+}
+
+impl Nodes {
+    /// Builds edges of state for every byte
+    /// (nodes inside UTF-8 sequences are added on the way)
+    /// - find_move: move of state for code point (None if it isn't matched)
+    fn lower(&mut self, find_move: impl Fn(u32) -> Option<Edge>) -> Vec<Option<Edge>> {
+        let mut row = vec![None; BYTES];
+
+        for byte in 0x00..0x80 {
+            row[byte as usize] = find_move(byte);
+        }
+
+        // 2-byte sequences
+        for lead in 0xC2..=0xDF {
+            row[lead as usize] = self.last_byte((lead & 0x1F) << 6, &find_move);
+        }
+
+        // 3-byte sequences (without overlong forms and surrogates)
+        for lead in 0xE0..=0xEF {
+            let seconds = match lead {
+                0xE0 => 0xA0..=0xBF,
+                0xED => 0x80..=0x9F,
+                _ => CONTINUATION
+            };
+
+            let edges = CONTINUATION
+                .map(|second| if seconds.contains(&second) {
+                    let prefix = ((lead & 0x0F) << 12) | ((second as u32 & 0x3F) << 6);
+
+                    self.last_byte(prefix, &find_move)
+                } else {
+                    None
+                })
+                .collect();
+
+            row[lead as usize] = self.node(edges);
+        }
+
+        // 4-byte sequences (without overlong forms and code points beyond U+10FFFF)
+        for lead in 0xF0..=0xF4 {
+            let seconds = match lead {
+                0xF0 => 0x90..=0xBF,
+                0xF4 => 0x80..=0x8F,
+                _ => CONTINUATION
+            };
+
+            let edges = CONTINUATION
+                .map(|second| if seconds.contains(&second) {
+                    let thirds = CONTINUATION
+                        .map(|third| {
+                            let prefix = ((lead & 0x07) << 18)
+                                | ((second as u32 & 0x3F) << 12)
+                                | ((third as u32 & 0x3F) << 6);
+
+                            self.last_byte(prefix, &find_move)
+                        })
+                        .collect();
+
+                    self.node(thirds)
+                } else {
+                    None
+                })
+                .collect();
+
+            row[lead as usize] = self.node(edges);
+        }
+
+        row
+    }
+
+    /// Returns edge to node before the last byte of UTF-8 sequence
+    /// - prefix: bits of code point that are set by previous bytes,
+    /// - find_move: move of state for code point
+    fn last_byte(&mut self, prefix: u32, find_move: &impl Fn(u32) -> Option<Edge>) -> Option<Edge> {
+        let edges = CONTINUATION
+            .map(|byte| find_move(prefix | (byte as u32 & 0x3F)))
+            .collect();
+
+        self.node(edges)
+    }
+
+    /// Returns edge to node that has given edges for continuation bytes
+    /// (or None if node has no edges)
+    /// - edges: edge for every continuation byte
+    fn node(&mut self, edges: Vec<Option<Edge>>) -> Option<Edge> {
+        if edges.iter().all(Option::is_none) {
+            return None;
+        }
+
+        if let Some(index) = self.indices.get(&edges) {
+            return Some(Edge::Node(*index));
+        }
+
+        let index = self.offset + self.rows.len();
+        self.rows.push(edges.clone());
+        self.indices.insert(edges, index);
+
+        Some(Edge::Node(index))
+    }
+}
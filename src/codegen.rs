@@ -0,0 +1,376 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::format;
+use crate::map::Map;
+use crate::fsm::FSM;
+use crate::vocabulary::Vocabulary;
+
+/// Generator of Rust function that runs strings through fixed FSM
+/// without any tables (transitions become arms of "match (state, ch)").
+/// Generated function has the same signature and results as "FSM::proceed":
+/// ```text
+/// pub fn name<'a>(
+///     string: &'a String,
+///     effector: Option<&'a mut dyn Effector<Effect, State, Error>>
+/// ) -> Result<(), FSMError<'a, State, Error>>
+/// ```
+/// Names of states, conditions and effects in vocabulary are Rust expressions,
+/// so they must be in scope of generated code (states are also used as patterns,
+/// e.g. unit variants of enum, and conditions are paths of functions).
+/// States get match arms in order of their registration. It's meant for build scripts:
+/// code is written into file in "OUT_DIR" and included by "include!"
+pub struct Codegen<State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    /// Name of generated function
+    name: String,
+    /// Path of type of states
+    state_type: String,
+    /// Path of type of effects
+    effect_type: String,
+    /// Path of type of effector errors
+    error_type: String,
+    /// Path of this crate
+    crate_path: String,
+    /// Expressions of states, conditions and effects
+    vocabulary: Vocabulary<State, Effect>
+}
+
+/// Error that occurs during code generation
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CodegenError<State> {
+    /// State of FSM isn't registered
+    UnnamedState(State),
+    /// Condition of transition has no name or its name isn't registered
+    UnnamedCondition {
+        from: State,
+        index: usize
+    },
+    /// Effect isn't registered
+    /// (index is None for post-effect)
+    UnnamedEffect {
+        from: State,
+        index: Option<usize>
+    },
+    /// Entry or exit effect of state isn't registered
+    UnnamedStateEffect(State),
+    /// Transition has guard or register operations
+    /// (that aren't generated)
+    GuardedTransition {
+        from: State,
+        index: usize
+    },
+    /// FSM has history pseudo-state
+    /// (that isn't generated)
+    History(State),
+    /// State has end tests of registers
+    /// (that aren't generated)
+    EndTest(State)
+}
+
+impl<State, Effect> Codegen<State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    /// Creates generator (effectors return errors of type "()")
+    /// - name: name of generated function,
+    /// - state_type: path of type of states,
+    /// - effect_type: path of type of effects,
+    /// - vocabulary: expressions of states, conditions and effects
+    pub fn new(name: &str, state_type: &str, effect_type: &str, vocabulary: Vocabulary<State, Effect>) -> Self {
+        Self {
+            name: String::from(name),
+            state_type: String::from(state_type),
+            effect_type: String::from(effect_type),
+            error_type: String::from("()"),
+            crate_path: String::from("fsm_rust_jb"),
+            vocabulary
+        }
+    }
+
+    /// Sets type of errors returned by effector
+    /// - error_type: path of type
+    pub fn error_type(mut self, error_type: &str) -> Self {
+        self.error_type = String::from(error_type);
+        self
+    }
+
+    /// Sets path of this crate (e.g. if it's renamed by dependent crate)
+    /// - crate_path: path of crate
+    pub fn crate_path(mut self, crate_path: &str) -> Self {
+        self.crate_path = String::from(crate_path);
+        self
+    }
+
+    /// Generates function that runs strings through FSM.
+    /// Transitions of every state (with inherited ones) become match arms in order,
+    /// and effects of every step (with exit and entry effects of states)
+    /// are dispatched as they're listed.
+    /// Guarded transitions, end tests of registers and history pseudo-states can't be generated
+    /// - fsm: source FSM
+    pub fn generate<Error>(&self, fsm: &FSM<State, Effect, Error>) -> Result<String, CodegenError<State>> {
+        let hierarchy = fsm.hierarchy();
+
+        if let Some(pseudo_state) = hierarchy.history_states().next() {
+            return Err(CodegenError::History(pseudo_state));
+        }
+
+        for state in fsm.states() {
+            self.state_name(state)?;
+
+            if fsm.end_tests(state).is_some() {
+                return Err(CodegenError::EndTest(state));
+            }
+        }
+
+        let types = format!("{}, {}, {}", self.effect_type, self.state_type, self.error_type);
+        let control_types = format!("{}, {}", self.state_type, self.error_type);
+        let start_state = fsm.start_state();
+
+        let mut start_path: Vec<State> = hierarchy.ancestors(start_state).collect();
+        start_path.reverse();
+
+        let mut start_effects = Vec::new();
+
+        for state in start_path {
+            if let Some(effect) = fsm.entry_effect(state) {
+                start_effects.push(self.effect_name(effect, CodegenError::UnnamedStateEffect(state))?);
+            }
+        }
+
+        let mut code = String::new();
+
+        code.push_str("/// Runs some string through FSM (see \"FSM::proceed\").\n");
+        code.push_str("/// Generated by fsm-rust-jb\n");
+        code.push_str("#[allow(unreachable_patterns, unused_mut)]\n");
+        code.push_str(&format!("pub fn {}<'a>(\n", self.name));
+        code.push_str("    string: &'a String,\n");
+        code.push_str(&format!(
+            "    mut effector: Option<&'a mut dyn {}::types::Effector<{}>>\n",
+            self.crate_path,
+            types
+        ));
+        code.push_str(&format!(
+            ") -> Result<(), {}::fsm::FSMError<'a, {}>> {{\n",
+            self.crate_path,
+            control_types
+        ));
+        code.push_str(&format!("    use {}::types::{{Control, Effector, StreamData}};\n", self.crate_path));
+        code.push_str(&format!("    use {}::fsm::FSMError;\n\n", self.crate_path));
+
+        code.push_str("    fn dispatch<'a, const N: usize>(\n");
+        code.push_str(&format!("        effector: &mut Option<&'a mut dyn Effector<{}>>,\n", types));
+        code.push_str(&format!("        effects: [{}; N],\n", self.effect_type));
+        code.push_str("        input_data: StreamData<'a>\n");
+        code.push_str(&format!("    ) -> Control<{}> {{\n", control_types));
+        code.push_str("        if let Some(effector) = effector.as_mut() {\n");
+        code.push_str("            for effect in IntoIterator::into_iter(effects) {\n");
+        code.push_str("                match effector.dispatch(effect, input_data) {\n");
+        code.push_str("                    Control::Continue => {},\n");
+        code.push_str("                    control => return control\n");
+        code.push_str("                }\n");
+        code.push_str("            }\n");
+        code.push_str("        }\n\n");
+        code.push_str("        Control::Continue\n");
+        code.push_str("    }\n\n");
+
+        code.push_str(&format!("    fn resolve(state: {}) -> {} {{\n", self.state_type, self.state_type));
+
+        let mut resolved = Vec::new();
+
+        for (state, name) in self.vocabulary.states() {
+            let target = hierarchy.resolve(state, &Map::new());
+
+            if target != state {
+                resolved.push(format!("            {} => {},\n", name, self.state_name(target)?));
+            }
+        }
+
+        if resolved.is_empty() {
+            code.push_str("        state\n");
+        } else {
+            code.push_str("        match state {\n");
+            code.push_str(&resolved.concat());
+            code.push_str("            state => state\n");
+            code.push_str("        }\n");
+        }
+
+        code.push_str("    }\n\n");
+
+        code.push_str(&format!("    let mut state = {};\n", self.state_name(start_state)?));
+        code.push_str(&format!(
+            "    let control = dispatch(&mut effector, [{}], StreamData {{ string, index: 0, character: None }});\n\n",
+            start_effects.join(", ")
+        ));
+        code.push_str(&Self::control_code("    ", true));
+        code.push('\n');
+
+        code.push_str("    for (index, ch) in string.chars().enumerate() {\n");
+        code.push_str("        let input_data = StreamData { string, index, character: Some(ch) };\n\n");
+        code.push_str("        let control = match (state, ch) {\n");
+
+        for (state, name) in self.vocabulary.states() {
+            if fsm.transitions(state).is_none() {
+                continue;
+            }
+
+            // transitions of state and then of its composite states (with their own positions)
+            let transitions = hierarchy.ancestors(state)
+                .filter_map(|ancestor| fsm.transitions(ancestor).map(|transitions| (ancestor, transitions)))
+                .flat_map(|(ancestor, transitions)| {
+                    transitions.iter()
+                        .enumerate()
+                        .map(move |(index, transition)| (ancestor, index, transition))
+                });
+
+            for (ancestor, index, transition) in transitions {
+                if transition.guard.is_some() || !transition.tests.is_empty() || !transition.updates.is_empty() {
+                    return Err(CodegenError::GuardedTransition { from: ancestor, index });
+                }
+
+                let condition = match transition.condition {
+                    Some(_) => Some(self.condition_name(transition.condition_name).ok_or(
+                        CodegenError::UnnamedCondition { from: ancestor, index }
+                    )?),
+                    None => None
+                };
+
+                let pattern = match (transition.symbol, condition) {
+                    (Some(symbol), Some(condition)) => format!("({}, ch @ {:?}) if {}(ch)", name, symbol, condition),
+                    (Some(symbol), None) => format!("({}, {:?})", name, symbol),
+                    (None, Some(condition)) => format!("({}, ch) if {}(ch)", name, condition),
+                    (None, None) => format!("({}, _)", name)
+                };
+
+                let target = hierarchy.resolve(transition.to, &Map::new());
+                let (left, entered) = hierarchy.transfer(state, target);
+
+                let mut effects = Vec::new();
+
+                for state in left {
+                    if let Some(effect) = fsm.exit_effect(state) {
+                        effects.push(self.effect_name(effect, CodegenError::UnnamedStateEffect(state))?);
+                    }
+                }
+
+                for effect in transition.effects.iter() {
+                    effects.push(self.effect_name(
+                        effect,
+                        CodegenError::UnnamedEffect { from: ancestor, index: Some(index) }
+                    )?);
+                }
+
+                for state in entered {
+                    if let Some(effect) = fsm.entry_effect(state) {
+                        effects.push(self.effect_name(effect, CodegenError::UnnamedStateEffect(state))?);
+                    }
+                }
+
+                code.push_str(&format!("            {} => {{\n", pattern));
+                code.push_str(&format!("                state = {};\n", self.state_name(target)?));
+                code.push_str(&format!(
+                    "                dispatch(&mut effector, [{}], input_data)\n",
+                    effects.join(", ")
+                ));
+                code.push_str("            },\n");
+            }
+
+            code.push_str(&format!(
+                "            ({}, _) => return Err(FSMError::NoValidTransition {{ from: state, input_data }}),\n",
+                name
+            ));
+        }
+
+        code.push_str("            (state, _) => return Err(FSMError::StateDoesNotExist(state))\n");
+        code.push_str("        };\n\n");
+        code.push_str(&Self::control_code("        ", true));
+        code.push_str("    }\n\n");
+
+        let mut post_effects = Vec::new();
+
+        for effect in fsm.post_effects().iter() {
+            post_effects.push(self.effect_name(
+                effect,
+                CodegenError::UnnamedEffect { from: fsm.initial_state(), index: None }
+            )?);
+        }
+
+        code.push_str("    let end_data = StreamData { string, index: string.len(), character: None };\n");
+        code.push_str("    let control = match state {\n");
+
+        for (state, name) in self.vocabulary.states() {
+            let mut effects = Vec::new();
+
+            for ancestor in hierarchy.ancestors(state) {
+                if let Some(effect) = fsm.exit_effect(ancestor) {
+                    effects.push(self.effect_name(effect, CodegenError::UnnamedStateEffect(ancestor))?);
+                }
+            }
+
+            if !effects.is_empty() {
+                effects.extend(post_effects.iter().cloned());
+
+                code.push_str(&format!(
+                    "        {} => dispatch(&mut effector, [{}], end_data),\n",
+                    name,
+                    effects.join(", ")
+                ));
+            }
+        }
+
+        code.push_str(&format!(
+            "        _ => dispatch(&mut effector, [{}], end_data)\n",
+            post_effects.join(", ")
+        ));
+        code.push_str("    };\n\n");
+        code.push_str(&Self::control_code("    ", false));
+        code.push_str("}\n");
+
+        Ok(code)
+    }
+
+    /// Generates handling of value returned by effector
+    /// - indent: indentation of code,
+    /// - is_step: whether run goes on after handling
+    fn control_code(indent: &str, is_step: bool) -> String {
+        let lines: &[&str] = if is_step {
+            &[
+                "match control {",
+                "    Control::Continue => {},",
+                "    Control::Stop => return Ok(()),",
+                "    Control::Error(error) => return Err(FSMError::Effect(error)),",
+                "    Control::GoTo(target) => state = resolve(target)",
+                "}"
+            ]
+        } else {
+            &[
+                "match control {",
+                "    Control::Error(error) => Err(FSMError::Effect(error)),",
+                "    _ => Ok(())",
+                "}"
+            ]
+        };
+
+        lines.iter()
+            .map(|line| format!("{}{}\n", indent, line))
+            .collect()
+    }
+
+    fn state_name(&self, state: State) -> Result<&str, CodegenError<State>> {
+        self.vocabulary.state_name(state).ok_or(CodegenError::UnnamedState(state))
+    }
+
+    /// Returns name of condition that transition refers to (if it's registered)
+    /// - name: name that transition gives its condition
+    fn condition_name(&self, name: Option<&'static str>) -> Option<&'static str> {
+        name.and_then(|name| self.vocabulary.condition_by_name(name))
+            .map(|(name, _)| name)
+    }
+
+    fn effect_name(&self, effect: &Effect, error: CodegenError<State>) -> Result<String, CodegenError<State>> {
+        self.vocabulary.effect_name(effect).map(String::from).ok_or(error)
+    }
+}
@@ -0,0 +1,840 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::ops::Range;
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::map::Map;
+use crate::types::{Effector, Control, StreamData};
+use crate::fsm::{FSM, FSMError, dispatch_all};
+
+/// Number of characters that are classified by table (ASCII)
+const CLASSIFIED_CHARS: usize = 128;
+
+/// Number of moves with effects that run of chunk records
+/// ahead of their dispatch (then it waits)
+#[cfg(feature = "std")]
+const RECORDED_MOVES: usize = 1024;
+
+/// Number of recorded moves that are sent to dispatch at once
+#[cfg(feature = "std")]
+const RECORDED_BATCH: usize = 256;
+
+/// FSM over characters compiled into dense tables for fast runs:
+/// states are mapped to indices and ASCII characters to classes
+/// (characters that match the same transitions in every state),
+/// so transition is found by single table lookup.
+/// Effects of every transition (including exit and entry effects of states)
+/// are collected in advance.
+/// Conditions are evaluated once while compiling, so they must be pure functions.
+/// Other characters are matched by transitions of FSM as usual
+pub struct CompiledFSM<State, Effect, Error = ()>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    /// Source FSM
+    fsm: FSM<State, Effect, Error>,
+    /// Indexed states with their moves
+    layout: Layout<State, Effect>,
+    /// Class of every ASCII character
+    classes: Vec<usize>,
+    /// Number of classes
+    class_count: usize,
+    /// Move of every state for every class (row per state)
+    table: Vec<Option<usize>>
+}
+
+/// States of FSM mapped to indices with every transition
+/// (and effects of run) collected in advance
+pub(crate) struct Layout<State, Effect> {
+    /// States by their indices
+    pub(crate) states: Vec<State>,
+    /// Indices of states
+    indices: Map<State, usize>,
+    /// Whether state has transitions (i.e. belongs to FSM)
+    pub(crate) is_known: Vec<bool>,
+    /// Index of state that run goes on from
+    /// when effector goes to state (composite states resolved)
+    resolved: Map<State, usize>,
+    /// Position of the first move of every state
+    /// (moves of state follow in order of its transitions)
+    pub(crate) offsets: Vec<usize>,
+    /// Every transition of every state with its target and effects
+    pub(crate) moves: Vec<Move<Effect>>,
+    /// Index of state that run starts from
+    pub(crate) start: usize,
+    /// Effects dispatched before first item
+    pub(crate) start_effects: Vec<Effect>,
+    /// Effects dispatched after last item in every state
+    /// (exit effects and post-effects)
+    end_effects: Vec<Vec<Effect>>
+}
+
+/// Matched transition with its target and all effects of step
+pub(crate) struct Move<Effect> {
+    pub(crate) to: usize,
+    pub(crate) effects: Vec<Effect>
+}
+
+/// Run through chunk of input from some state without effects
+#[cfg(feature = "std")]
+#[derive(Copy, Clone)]
+enum Outcome {
+    /// Chunk is proceeded and run ends in state
+    End(usize),
+    /// Character (with its position in chunk) is rejected in state
+    Rejected {
+        state: usize,
+        index: usize,
+        character: char
+    }
+}
+
+/// Move with effects made in chunk: byte and character positions of character
+/// (in chunk), character and index of move
+#[cfg(feature = "std")]
+type RecordedMove = (usize, usize, char, usize);
+
+/// End of dispatch of recorded effects
+#[cfg(feature = "std")]
+enum Replay {
+    /// Effects of every chunk are dispatched
+    Finished,
+    /// Effector stopped run
+    Stopped,
+    /// Effector went to state, so rest of string
+    /// (from byte and character positions) is run sequentially
+    Redirected {
+        state: usize,
+        byte_start: usize,
+        char_start: usize
+    }
+}
+
+/// Error that occurs during compilation of FSM
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CompileError<State> {
+    /// Transition has guard or register operations
+    /// (that depend on run)
+    GuardedTransition {
+        from: State,
+        index: usize
+    },
+    /// FSM has history pseudo-state
+    /// (that depends on run)
+    History(State),
+    /// State has end tests of registers
+    /// (that depend on run)
+    EndTest(State)
+}
+
+impl<State, Effect, Error> CompiledFSM<State, Effect, Error>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    /// Compiles FSM
+    /// - fsm: FSM without guards, registers and history pseudo-states
+    pub fn new(fsm: FSM<State, Effect, Error>) -> Result<Self, CompileError<State>> {
+        let layout = Layout::new(&fsm)?;
+
+        let (classes, class_count, table) = classify(
+            CLASSIFIED_CHARS,
+            layout.states.len(),
+            |state, code| layout.find_move(&fsm, state, code as u8 as char)
+        );
+
+        Ok(Self {
+            fsm,
+            layout,
+            classes,
+            class_count,
+            table
+        })
+    }
+
+    /// Returns source FSM
+    pub fn fsm(&self) -> &FSM<State, Effect, Error> {
+        &self.fsm
+    }
+
+    /// Returns number of classes of ASCII characters
+    pub fn class_count(&self) -> usize {
+        self.class_count
+    }
+
+    /// Runs some string through compiled FSM (see "FSM::proceed").
+    /// Run goes the same way as run of source FSM, except that
+    /// state which effector goes to must belong to FSM
+    /// - string: runnable string,
+    /// - effector: module that mutates some data by effects
+    pub fn proceed<'a>(
+        &self,
+        string: &'a String,
+        mut effector: Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<(), FSMError<'a, State, Error>> {
+        let layout = &self.layout;
+        let mut state = layout.start;
+
+        let control = dispatch_all(
+            &mut effector,
+            layout.start_effects.iter(),
+            StreamData {
+                string,
+                index: 0,
+                character: None
+            },
+            None
+        );
+
+        if !layout.control(control, &mut state)? {
+            return Ok(());
+        }
+
+        self.run_from(string, state, 0, 0, &mut effector)
+    }
+
+    /// Runs rest of string from some state dispatching effects
+    /// (and then effects after last character)
+    /// - string: runnable string,
+    /// - state: index of current state,
+    /// - byte_start: byte position of the first character of rest,
+    /// - char_start: position of the first character of rest,
+    /// - effector: module that mutates some data by effects
+    fn run_from<'a>(
+        &self,
+        string: &'a String,
+        state: usize,
+        byte_start: usize,
+        char_start: usize,
+        effector: &mut Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<(), FSMError<'a, State, Error>> {
+        let state = match self.run_span(string, state, byte_start..string.len(), char_start, effector)? {
+            Some(state) => state,
+            None => return Ok(())
+        };
+
+        self.layout.end(effector, state, StreamData {
+            string,
+            index: string.len(),
+            character: None
+        })
+    }
+
+    /// Runs part of string from some state dispatching effects
+    /// and returns state that run ends in (or None if effector stopped it)
+    /// - string: runnable string,
+    /// - state: index of current state,
+    /// - bytes: byte positions of part,
+    /// - char_start: position of the first character of part,
+    /// - effector: module that mutates some data by effects
+    fn run_span<'a>(
+        &self,
+        string: &'a String,
+        mut state: usize,
+        bytes: Range<usize>,
+        char_start: usize,
+        effector: &mut Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<Option<usize>, FSMError<'a, State, Error>> {
+        let layout = &self.layout;
+
+        for (char_id, ch) in string[bytes].chars().enumerate() {
+            let step = match self.next_move(state, ch) {
+                Some(move_id) => &layout.moves[move_id],
+                None => return Err(self.rejection(string, state, char_start + char_id, ch))
+            };
+
+            state = step.to;
+
+            let control = dispatch_all(
+                effector,
+                step.effects.iter(),
+                StreamData {
+                    string,
+                    index: char_start + char_id,
+                    character: Some(ch)
+                },
+                None
+            );
+
+            if !layout.control(control, &mut state)? {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Finds move of state for character
+    /// (or None if state isn't known or no transition matches)
+    /// - state: index of current state,
+    /// - ch: current character
+    fn next_move(&self, state: usize, ch: char) -> Option<usize> {
+        let code = ch as usize;
+
+        if code < CLASSIFIED_CHARS {
+            self.table[state * self.class_count + self.classes[code]]
+        } else {
+            self.layout.find_move(&self.fsm, state, ch)
+        }
+    }
+
+    /// Returns error of run that has no move for character
+    /// - string: runnable string,
+    /// - state: index of current state,
+    /// - index: position of character,
+    /// - ch: rejected character
+    fn rejection<'a>(&self, string: &'a String, state: usize, index: usize, ch: char) -> FSMError<'a, State, Error> {
+        let from = self.layout.states[state];
+
+        if self.layout.is_known[state] {
+            FSMError::NoValidTransition {
+                from,
+                input_data: StreamData {
+                    string,
+                    index,
+                    character: Some(ch)
+                }
+            }
+        } else {
+            FSMError::StateDoesNotExist(from)
+        }
+    }
+}
+
+/// Parallel runs (for FSM with small number of states)
+#[cfg(feature = "std")]
+impl<State, Effect, Error> CompiledFSM<State, Effect, Error>
+    where State: Eq + PartialEq + Copy + Hash + Debug + Sync,
+          Effect: Clone + Sync
+{
+    /// Runs some string through compiled FSM like "proceed" does,
+    /// but splits it into chunks that are run by threads.
+    /// The first chunk is run (with effects) from initial state, while every other chunk
+    /// is run from every state at once (runs that reach the same state go on as one run,
+    /// so chunk is usually scanned about once). Then runs of chunks are chained,
+    /// moves with effects are recorded by threads and effects are dispatched in order
+    /// as they come (threads wait while too many moves of their chunks aren't dispatched yet).
+    /// It's worth it only if there are more chunks than states (and as many cores as chunks),
+    /// otherwise string is run sequentially.
+    /// If effector goes to some state in later chunk, the rest of string is run sequentially
+    /// - string: runnable string,
+    /// - chunks: number of chunks (and threads),
+    /// - effector: module that mutates some data by effects
+    pub fn proceed_parallel<'a>(
+        &self,
+        string: &'a String,
+        chunks: usize,
+        mut effector: Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<(), FSMError<'a, State, Error>> {
+        let layout = &self.layout;
+        let chunks = split(string, chunks);
+
+        if chunks.len() <= layout.states.len() {
+            return self.proceed(string, effector);
+        }
+
+        let mut state = layout.start;
+
+        let control = dispatch_all(
+            &mut effector,
+            layout.start_effects.iter(),
+            StreamData {
+                string,
+                index: 0,
+                character: None
+            },
+            None
+        );
+
+        if !layout.control(control, &mut state)? {
+            return Ok(());
+        }
+
+        let (first_start, first_chunk) = chunks[0];
+        let first_bytes = first_start..first_start + first_chunk.len();
+
+        // the first chunk is run here while other chunks are run from every state
+        // (end of run from every state with number of characters of chunk)
+        let (first_end, outcomes) = std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks[1..].iter()
+                .map(|(_, chunk)| scope.spawn(move || (self.outcomes(chunk), chunk.chars().count())))
+                .collect();
+
+            let first_end = self.run_span(string, state, first_bytes, 0, &mut effector);
+            let outcomes: Vec<(Vec<Outcome>, usize)> = handles.into_iter()
+                .map(|handle| handle.join().expect("chunk run panicked"))
+                .collect();
+
+            (first_end, outcomes)
+        });
+
+        state = match first_end? {
+            Some(state) => state,
+            None => return Ok(())
+        };
+
+        // state and character position that every later chunk starts from
+        let mut starts = Vec::with_capacity(outcomes.len());
+        let mut char_start = first_chunk.chars().count();
+        let mut rejection = None;
+
+        for (chunk_outcomes, char_count) in outcomes.iter() {
+            starts.push((state, char_start));
+
+            match chunk_outcomes[state] {
+                Outcome::End(end) => state = end,
+                Outcome::Rejected { state, index, character } => {
+                    rejection = Some(self.rejection(string, state, char_start + index, character));
+                    break;
+                }
+            }
+
+            char_start += char_count;
+        }
+
+        if effector.is_some() {
+            // receivers are dropped when dispatch ends, so threads stop recording
+            let replay = std::thread::scope(|scope| {
+                let receivers: Vec<_> = starts.iter()
+                    .zip(chunks[1..].iter())
+                    .map(|((state, _), (_, chunk))| {
+                        let (sender, receiver) = std::sync::mpsc::sync_channel(RECORDED_MOVES / RECORDED_BATCH);
+                        scope.spawn(move || self.record(*state, chunk, sender));
+                        receiver
+                    })
+                    .collect();
+
+                for ((receiver, (byte_start, _)), (_, char_start)) in receivers.iter()
+                    .zip(chunks[1..].iter())
+                    .zip(starts.iter())
+                {
+                    for (byte_offset, char_offset, ch, move_id) in receiver.iter().flatten() {
+                        let step = &layout.moves[move_id];
+                        let mut next_state = step.to;
+
+                        let control = dispatch_all(
+                            &mut effector,
+                            step.effects.iter(),
+                            StreamData {
+                                string,
+                                index: char_start + char_offset,
+                                character: Some(ch)
+                            },
+                            None
+                        );
+
+                        let is_redirected = matches!(control, Control::GoTo(_));
+
+                        if !layout.control(control, &mut next_state)? {
+                            return Ok(Replay::Stopped);
+                        }
+
+                        if is_redirected {
+                            return Ok(Replay::Redirected {
+                                state: next_state,
+                                byte_start: byte_start + byte_offset + ch.len_utf8(),
+                                char_start: char_start + char_offset + 1
+                            });
+                        }
+                    }
+                }
+
+                Ok(Replay::Finished)
+            })?;
+
+            match replay {
+                Replay::Finished => {},
+                Replay::Stopped => return Ok(()),
+                Replay::Redirected { state, byte_start, char_start } => {
+                    return self.run_from(string, state, byte_start, char_start, &mut effector);
+                }
+            }
+        }
+
+        if let Some(error) = rejection {
+            return Err(error);
+        }
+
+        layout.end(&mut effector, state, StreamData {
+            string,
+            index: string.len(),
+            character: None
+        })
+    }
+
+    /// Runs chunk from every state without effects
+    /// (runs that reach the same state go on as one run)
+    /// and returns end of run from every state
+    /// - chunk: part of string
+    fn outcomes(&self, chunk: &str) -> Vec<Outcome> {
+        let state_count = self.layout.states.len();
+        let mut outcomes = vec![Outcome::End(0); state_count];
+        // current state of every distinct run with states it started from
+        let mut runs: Vec<(usize, Vec<usize>)> = (0..state_count)
+            .map(|state| (state, vec![state]))
+            .collect();
+        let mut chars = chunk.chars().enumerate();
+
+        while runs.len() > 1 {
+            let (index, ch) = match chars.next() {
+                Some(item) => item,
+                None => break
+            };
+
+            runs.retain_mut(|(state, starts)| match self.next_move(*state, ch) {
+                Some(move_id) => {
+                    *state = self.layout.moves[move_id].to;
+                    true
+                },
+                None => {
+                    for start in starts.iter() {
+                        outcomes[*start] = Outcome::Rejected {
+                            state: *state,
+                            index,
+                            character: ch
+                        };
+                    }
+
+                    false
+                }
+            });
+
+            runs.sort_unstable_by_key(|(state, _)| *state);
+            runs.dedup_by(|(state, starts), (kept_state, kept_starts)| {
+                let is_merged = state == kept_state;
+
+                if is_merged {
+                    kept_starts.append(starts);
+                }
+
+                is_merged
+            });
+        }
+
+        // the last run goes on alone
+        if let [(state, starts)] = runs.as_mut_slice() {
+            for (index, ch) in chars {
+                *state = match self.next_move(*state, ch) {
+                    Some(move_id) => self.layout.moves[move_id].to,
+                    None => {
+                        for start in starts.iter() {
+                            outcomes[*start] = Outcome::Rejected {
+                                state: *state,
+                                index,
+                                character: ch
+                            };
+                        }
+
+                        return outcomes;
+                    }
+                };
+            }
+        }
+
+        for (state, starts) in runs {
+            for start in starts {
+                outcomes[start] = Outcome::End(state);
+            }
+        }
+
+        outcomes
+    }
+
+    /// Runs chunk from state and sends moves with effects
+    /// (until chunk is rejected or receiver is dropped)
+    /// - state: index of state,
+    /// - chunk: part of string,
+    /// - sender: channel of recorded moves
+    fn record(&self, mut state: usize, chunk: &str, sender: std::sync::mpsc::SyncSender<Vec<RecordedMove>>) {
+        let mut batch = Vec::with_capacity(RECORDED_BATCH);
+
+        for (char_offset, (byte_offset, ch)) in chunk.char_indices().enumerate() {
+            let move_id = match self.next_move(state, ch) {
+                Some(move_id) => move_id,
+                None => break
+            };
+
+            let step = &self.layout.moves[move_id];
+
+            if !step.effects.is_empty() {
+                batch.push((byte_offset, char_offset, ch, move_id));
+            }
+
+            if batch.len() == RECORDED_BATCH {
+                let full = std::mem::replace(&mut batch, Vec::with_capacity(RECORDED_BATCH));
+
+                if sender.send(full).is_err() {
+                    return;
+                }
+            }
+
+            state = step.to;
+        }
+
+        if !batch.is_empty() {
+            // receiver may be dropped already
+            let _ = sender.send(batch);
+        }
+    }
+}
+
+impl<State, Effect> Layout<State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    /// Indexes states of FSM and collects their moves
+    /// - fsm: FSM without guards, registers and history pseudo-states
+    pub(crate) fn new<Error, Input>(
+        fsm: &FSM<State, Effect, Error, (), Input>
+    ) -> Result<Self, CompileError<State>>
+        where Input: Copy + PartialEq
+    {
+        let hierarchy = fsm.hierarchy();
+
+        if let Some(pseudo_state) = hierarchy.history_states().next() {
+            return Err(CompileError::History(pseudo_state));
+        }
+
+        for state in fsm.states() {
+            for (index, transition) in fsm.transitions(state).into_iter().flatten().enumerate() {
+                if transition.guard.is_some() || !transition.tests.is_empty() || !transition.updates.is_empty() {
+                    return Err(CompileError::GuardedTransition { from: state, index });
+                }
+            }
+
+            if fsm.end_tests(state).is_some() {
+                return Err(CompileError::EndTest(state));
+            }
+        }
+
+        let mut layout = Self {
+            states: Vec::new(),
+            indices: Map::new(),
+            is_known: Vec::new(),
+            resolved: Map::new(),
+            offsets: Vec::new(),
+            moves: Vec::new(),
+            start: 0,
+            start_effects: Vec::new(),
+            end_effects: Vec::new()
+        };
+
+        let start_state = fsm.start_state();
+        layout.start = layout.index(start_state);
+
+        let mut path: Vec<State> = hierarchy.ancestors(start_state).collect();
+        path.reverse();
+        layout.start_effects = path.iter()
+            .filter_map(|state| fsm.entry_effect(*state).cloned())
+            .collect();
+
+        let sources: Vec<State> = fsm.states().collect();
+
+        for state in sources.iter() {
+            layout.index(*state);
+        }
+
+        // moves of known states (targets are indexed on the way)
+        let mut state_moves = Map::new();
+
+        for state in sources.iter() {
+            let moves: Vec<(State, Vec<Effect>)> = hierarchy.ancestors(*state)
+                .filter_map(|state| fsm.transitions(state))
+                .flatten()
+                .map(|transition| {
+                    let target = hierarchy.resolve(transition.to, &Map::new());
+                    let (left, entered) = hierarchy.transfer(*state, target);
+                    let effects = fsm.step_effects(left, &transition.effects, entered)
+                        .cloned()
+                        .collect();
+
+                    (target, effects)
+                })
+                .collect();
+
+            for (target, _) in moves.iter() {
+                layout.index(*target);
+            }
+
+            state_moves.insert(*state, moves);
+        }
+
+        for state in layout.states.clone() {
+            let resolved = layout.index(hierarchy.resolve(state, &Map::new()));
+            layout.resolved.insert(state, resolved);
+        }
+
+        for index in 0..layout.states.len() {
+            let state = layout.states[index];
+
+            layout.offsets.push(layout.moves.len());
+            layout.is_known.push(state_moves.contains_key(&state));
+
+            for (target, effects) in state_moves.remove(&state).into_iter().flatten() {
+                layout.moves.push(Move {
+                    to: layout.indices[&target],
+                    effects
+                });
+            }
+
+            layout.end_effects.push(
+                hierarchy.ancestors(state)
+                    .filter_map(|state| fsm.exit_effect(state).cloned())
+                    .chain(fsm.post_effects().iter().cloned())
+                    .collect()
+            );
+        }
+
+        Ok(layout)
+    }
+
+    /// Finds move of state for input item by transitions of FSM
+    /// (or None if state isn't known or no transition matches)
+    /// - fsm: source FSM,
+    /// - state: index of current state,
+    /// - ch: current item
+    pub(crate) fn find_move<Error, Input>(
+        &self,
+        fsm: &FSM<State, Effect, Error, (), Input>,
+        state: usize,
+        ch: Input
+    ) -> Option<usize>
+        where Input: Copy + PartialEq
+    {
+        if !self.is_known[state] {
+            return None;
+        }
+
+        fsm.match_transition(self.states[state], ch, None, &[])
+            .map(|(trans_id, ..)| self.offsets[state] + trans_id)
+    }
+
+    /// Applies value returned by effector to run
+    /// and tells whether run goes on
+    /// - control: value returned by effector,
+    /// - state: index of current state
+    pub(crate) fn control<'a, Error, Input>(
+        &self,
+        control: Control<State, Error>,
+        state: &mut usize
+    ) -> Result<bool, FSMError<'a, State, Error, Input>> {
+        match control {
+            Control::Continue => Ok(true),
+            Control::Stop => Ok(false),
+            Control::Error(error) => Err(FSMError::Effect(error)),
+            Control::GoTo(target) => {
+                *state = *self.resolved.get(&target).ok_or(FSMError::StateDoesNotExist(target))?;
+
+                Ok(true)
+            }
+        }
+    }
+
+    /// Dispatches effects after last item
+    /// - effector: module that mutates some data by effects,
+    /// - state: index of last state,
+    /// - input_data: position after last item
+    pub(crate) fn end<'a, Error, Input>(
+        &self,
+        effector: &mut Option<&'a mut dyn Effector<Effect, State, Error, Input>>,
+        state: usize,
+        input_data: StreamData<'a, Input>
+    ) -> Result<(), FSMError<'a, State, Error, Input>>
+        where Input: Copy + PartialEq
+    {
+        let control = dispatch_all(
+            effector,
+            self.end_effects[state].iter(),
+            input_data,
+            None
+        );
+
+        match control {
+            Control::Error(error) => Err(FSMError::Effect(error)),
+            _ => Ok(())
+        }
+    }
+
+    /// Returns index of state (adds state if it has no index yet)
+    /// - state: state of FSM
+    fn index(&mut self, state: State) -> usize {
+        if let Some(index) = self.indices.get(&state) {
+            return *index;
+        }
+
+        self.states.push(state);
+        self.indices.insert(state, self.states.len() - 1);
+
+        self.states.len() - 1
+    }
+}
+
+/// Splits symbols (e.g. ASCII characters or bytes) into classes
+/// (symbols that have the same entry in every row)
+/// and returns class of every symbol, number of classes and table
+/// with entry of every row for every class (row after row)
+/// - symbols: number of symbols,
+/// - rows: number of rows (e.g. states),
+/// - entry: entry of row for symbol
+pub(crate) fn classify<T>(
+    symbols: usize,
+    rows: usize,
+    entry: impl Fn(usize, usize) -> Option<T>
+) -> (Vec<usize>, usize, Vec<Option<T>>)
+    where T: Clone + Eq + Hash
+{
+    let mut signatures: Map<Vec<Option<T>>, usize> = Map::new();
+    let mut class_entries = Vec::new();
+    let mut classes = Vec::with_capacity(symbols);
+
+    for symbol in 0..symbols {
+        let signature: Vec<Option<T>> = (0..rows)
+            .map(|row| entry(row, symbol))
+            .collect();
+
+        let class_count = signatures.len();
+        let class = *signatures.entry(signature.clone()).or_insert_with(|| {
+            class_entries.push(signature);
+            class_count
+        });
+
+        classes.push(class);
+    }
+
+    let class_count = class_entries.len();
+    let mut table = vec![None; rows * class_count];
+
+    for (class, entries) in class_entries.into_iter().enumerate() {
+        for (row, entry) in entries.into_iter().enumerate() {
+            table[row * class_count + class] = entry;
+        }
+    }
+
+    (classes, class_count, table)
+}
+
+/// Splits string into at most given number of chunks of similar length
+/// (at character boundaries) and returns them with their byte positions
+/// - string: split string,
+/// - chunks: number of chunks
+#[cfg(feature = "std")]
+fn split(string: &str, chunks: usize) -> Vec<(usize, &str)> {
+    let chunks = chunks.clamp(1, string.len().max(1));
+    let mut parts = Vec::with_capacity(chunks);
+    let mut start = 0;
+
+    for chunk_id in 1..=chunks {
+        let mut end = string.len() * chunk_id / chunks;
+
+        while !string.is_char_boundary(end) {
+            end += 1;
+        }
+
+        if end > start || chunk_id == chunks {
+            parts.push((start, &string[start..end]));
+            start = end;
+        }
+    }
+
+    parts
+}
@@ -0,0 +1,156 @@
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+use crate::types::{Effector, Control, StreamData};
+
+/// Handler of single effect registered in "HandlerEffector"
+type Handler<Data, State, Error, Input> = 
+    Box<dyn FnMut(&mut Data, StreamData<Input>) -> Control<State, Error>>;
+
+/// Effector that owns some data and mutates it
+/// by closures registered per effect value
+/// (so there's no need to define effector type with big "match").
+/// Effects without handlers are ignored
+pub struct HandlerEffector<Data, Effect, State, Error = (), Input = char>
+    where Effect: Clone + PartialEq
+{
+    /// Data mutated by handlers
+    data: Data,
+    /// Handlers in order of registration
+    handlers: Vec<(Effect, Handler<Data, State, Error, Input>)>
+}
+
+/// Effector that fans every dispatch out to several effectors in order.
+/// Effectors receive effect until some of them returns
+/// anything but "Control::Continue" (that value is returned to FSM)
+pub struct FanOut<'e, Effect, State, Error = (), Input = char>
+    where Effect: Clone
+{
+    effectors: Vec<&'e mut dyn Effector<Effect, State, Error, Input>>
+}
+
+impl<Data, Effect, State, Error, Input> HandlerEffector<Data, Effect, State, Error, Input>
+    where Effect: Clone + PartialEq
+{
+    /// Creates effector without handlers
+    /// - data: data mutated by handlers
+    pub fn new(data: Data) -> Self {
+        Self {
+            data,
+            handlers: Vec::new()
+        }
+    }
+
+    /// Registers handler of effect that doesn't control run
+    /// (several handlers of the same effect are called in order of registration)
+    /// - effect: handled effect,
+    /// - handler: closure that mutates data
+    pub fn on<F>(self, effect: Effect, mut handler: F) -> Self
+        where F: FnMut(&mut Data, StreamData<Input>) + 'static
+    {
+        self.on_control(effect, move |data, input_data| {
+            handler(data, input_data);
+            Control::Continue
+        })
+    }
+
+    /// Registers handler of effect that controls run (see "Control")
+    /// - effect: handled effect,
+    /// - handler: closure that mutates data
+    pub fn on_control<F>(mut self, effect: Effect, handler: F) -> Self
+        where F: FnMut(&mut Data, StreamData<Input>) -> Control<State, Error> + 'static
+    {
+        self.handlers.push((effect, Box::new(handler)));
+        self
+    }
+
+    /// Returns data mutated by handlers
+    pub fn data(&self) -> &Data {
+        &self.data
+    }
+
+    /// Returns mutable data
+    pub fn data_mut(&mut self) -> &mut Data {
+        &mut self.data
+    }
+
+    /// Takes data mutated by handlers (handlers are dropped)
+    pub fn into_data(self) -> Data {
+        self.data
+    }
+}
+
+impl<Data, Effect, State, Error, Input> Effector<Effect, State, Error, Input>
+    for HandlerEffector<Data, Effect, State, Error, Input>
+    where Effect: Clone + PartialEq,
+          Input: Copy
+{
+    fn dispatch(&mut self, effect: Effect, input_data: StreamData<Input>) -> Control<State, Error> {
+        for (handled, handler) in self.handlers.iter_mut() {
+            if *handled != effect {
+                continue;
+            }
+
+            match handler(&mut self.data, input_data) {
+                Control::Continue => {},
+                control => return control
+            }
+        }
+
+        Control::Continue
+    }
+}
+
+impl<'e, Effect, State, Error, Input> FanOut<'e, Effect, State, Error, Input>
+    where Effect: Clone
+{
+    /// Creates fan-out without effectors
+    pub fn new() -> Self {
+        Self {
+            effectors: Vec::new()
+        }
+    }
+
+    /// Appends effector to the end of fan-out
+    /// - effector: module that mutates some data by effects
+    pub fn with(mut self, effector: &'e mut dyn Effector<Effect, State, Error, Input>) -> Self {
+        self.effectors.push(effector);
+        self
+    }
+}
+
+impl<'e, Effect, State, Error, Input> Default for FanOut<'e, Effect, State, Error, Input>
+    where Effect: Clone
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'e, Effect, State, Error, Input> Effector<Effect, State, Error, Input>
+    for FanOut<'e, Effect, State, Error, Input>
+    where Effect: Clone,
+          Input: Copy
+{
+    fn dispatch(&mut self, effect: Effect, input_data: StreamData<Input>) -> Control<State, Error> {
+        for effector in self.effectors.iter_mut() {
+            match effector.dispatch(effect.clone(), input_data) {
+                Control::Continue => {},
+                control => return control
+            }
+        }
+
+        Control::Continue
+    }
+}
+
+/// Any closure over effect and stream data is effector
+/// that never controls run
+impl<F, Effect, State, Error, Input> Effector<Effect, State, Error, Input> for F
+    where F: FnMut(Effect, StreamData<Input>),
+          Effect: Clone
+{
+    fn dispatch(&mut self, effect: Effect, input_data: StreamData<Input>) -> Control<State, Error> {
+        self(effect, input_data);
+        Control::Continue
+    }
+}
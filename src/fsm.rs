@@ -1,39 +1,118 @@
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::fmt::Debug;
-use crate::types::{Transition, Effector, StreamData, StatesConnection};
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::map::Map;
+use crate::types::{Transition, Effector, Control, Observer, StreamData, StatesConnection};
+use crate::trace::{Trace, TraceStep};
+use crate::registers::{self, RegisterTest, RegisterIssue, register_value};
+use crate::hierarchy::{Hierarchy, History};
 
-/// Finite state machine with side effects (Mealy automata)
-pub struct FSM<State, Effect>
+/// Finite state machine with side effects (Mealy automata).
+/// Error is type of errors that effectors may return
+/// to abort run (see "Control"),
+/// Context is type of data read by guards of transitions (see "proceed_in_context"),
+/// Input is type of items of stream (characters by default, see "proceed_iter").
+/// Runs never change FSM, and FSM is Send and Sync whenever states, effects and items are
+/// (conditions and guards are plain functions), so it may be shared between threads
+/// (see "Runner" for run that is fed item by item)
+pub struct FSM<State, Effect, Error = (), Context = (), Input = char>
     where State: Eq + PartialEq + Copy + Hash,
-          Effect: Copy,
+          Effect: Clone,
 {
     /// State at beginning of running through stream
     initial_state: State,
     /// Transition graph that connects every state of FSM
     /// to some next states by transitions
-    transition_table: HashMap<State, Vec<Transition<State, Effect>>>,
-    /// - post_effect: side effect that occurs after proceeding 
-    /// last character of string (ref. as "post-effect") 
-    post_effect: Option<Effect>
+    transition_table: Map<State, Vec<Transition<State, Effect, Context, Input>>>,
+    /// Side effects that occur (in order) after proceeding 
+    /// last character of string (ref. as "post-effects") 
+    post_effects: Vec<Effect>,
+    /// Side effects that occur on entering some states (Moore-style)
+    entry_effects: Map<State, Effect>,
+    /// Side effects that occur on leaving some states (Moore-style)
+    exit_effects: Map<State, Effect>,
+    /// Nesting of states (statechart)
+    hierarchy: Hierarchy<State>,
+    /// Tests of registers that must hold if input ends in some states
+    end_tests: Map<State, Vec<RegisterTest>>,
+    error: PhantomData<fn() -> Error>
 }
 
+/// Fails to compile if FSM isn't Send and Sync
+/// for any types of errors and contexts (raw pointer is neither Send nor Sync)
+const _: fn() = || {
+    fn is_send_sync<T: Send + Sync>() {}
+
+    is_send_sync::<FSM<u8, u8, *const (), *const (), char>>();
+};
+
 /// Error that occurs during initialization or running with FSM
 #[derive(Copy, Clone, Debug)]
-pub enum FSMError<'a, State> 
-    where State: Eq + PartialEq + Copy + Hash + Debug
+pub enum FSMError<'a, State, Error = (), Input = char> 
+    where State: Eq + PartialEq + Copy + Debug
 {
     StateDoesNotExist(State),
     TransDoesNotExist(StatesConnection<State>),
     NoValidTransition {
         from: State,
-        input_data: StreamData<'a>
-    }
+        input_data: StreamData<'a, Input>
+    },
+    /// Error returned by effector
+    Effect(Error),
+    /// Stack of pushdown automata is popped while it's empty
+    StackUnderflow {
+        from: State,
+        input_data: StreamData<'a, Input>
+    },
+    /// Stack of pushdown automata isn't empty after last character
+    NonEmptyStack {
+        state: State,
+        depth: usize
+    },
+    /// State would become its own composite state
+    CyclicHierarchy(State),
+    /// State isn't nested into composite state
+    NotChild {
+        parent: State,
+        child: State
+    },
+    /// Input ends in state whose end test of registers doesn't hold
+    /// (see "add_end_test")
+    RejectedEnd {
+        state: State,
+        index: usize
+    },
+    /// Run that already failed is fed or finished (see "Runner")
+    RunFailed
 }
 
-impl<State, Effect> FSM<State, Effect> 
+/// Progress of single run through input
+pub(crate) struct Cursor<State> {
+    /// Current (innermost) state
+    pub(crate) state: State,
+    /// Number of proceeded items
+    pub(crate) length: usize,
+    /// Values of registers
+    registers: Vec<u64>,
+    /// Last active innermost state of every left composite state (for history)
+    pub(crate) remembered: Map<State, State>,
+    /// Run is stopped by effector or reached end of input
+    pub(crate) is_finished: bool
+}
+
+/// Position of matched transition, transition itself, its target and effects
+pub(crate) type MatchedTransition<'t, State, Effect, Context, Input> = 
+    (usize, &'t Transition<State, Effect, Context, Input>, State, &'t [Effect]);
+
+/// Input string of runs through items of other type than characters
+pub(crate) static EMPTY_STRING: String = String::new();
+
+impl<State, Effect, Error, Context, Input> FSM<State, Effect, Error, Context, Input> 
     where State: Eq + PartialEq + Copy + Hash + Debug,
-          Effect: Copy,
+          Effect: Clone,
+          Input: Copy + PartialEq
 {
     /// Creates new instance of FSM
     /// - initial_state: starting state,
@@ -41,50 +120,257 @@ impl<State, Effect> FSM<State, Effect>
     /// - post_effect: post-effect
     pub fn new<'a>(
         initial_state: State, 
-        transition_table: HashMap<State, Vec<Transition<State, Effect>>>,
+        transition_table: Map<State, Vec<Transition<State, Effect, Context, Input>>>,
         post_effect: Option<Effect>
-    ) -> Result<Self, FSMError<'a, State>> {
+    ) -> Result<Self, FSMError<'a, State, Error, Input>> {
         if !transition_table.contains_key(&initial_state) {
             Err(FSMError::StateDoesNotExist(initial_state))
         } else {
             Ok(Self {
                 initial_state,
                 transition_table,
-                post_effect
+                post_effects: post_effect.into_iter().collect(),
+                entry_effects: Map::new(),
+                exit_effects: Map::new(),
+                hierarchy: Hierarchy::new(),
+                end_tests: Map::new(),
+                error: PhantomData
             })
         }
     }
 
+    /// Returns starting state
+    pub fn initial_state(&self) -> State {
+        self.initial_state
+    }
+
+    /// Returns ordered transitions of some state
+    /// (or None if state doesn't belong to FSM)
+    /// - state: source state
+    pub fn transitions(&self, state: State) -> Option<&Vec<Transition<State, Effect, Context, Input>>> {
+        self.transition_table.get(&state)
+    }
+
+    /// Returns iterator over all states of FSM (in arbitrary order)
+    pub fn states(&self) -> impl Iterator<Item = State> + '_ {
+        self.transition_table.keys().copied()
+    }
+
+    /// Returns post-effects in order of dispatch
+    pub fn post_effects(&self) -> &Vec<Effect> {
+        &self.post_effects
+    }
+
+    /// Appends effect to the end of post-effects
+    /// - effect: side effect
+    pub fn push_post_effect(&mut self, effect: Effect) {
+        self.post_effects.push(effect);
+    }
+
+    /// Returns effect that occurs on entering state
+    /// - state: entered state
+    pub fn entry_effect(&self, state: State) -> Option<&Effect> {
+        self.entry_effects.get(&state)
+    }
+
+    /// Returns effect that occurs on leaving state
+    /// - state: left state
+    pub fn exit_effect(&self, state: State) -> Option<&Effect> {
+        self.exit_effects.get(&state)
+    }
+
+    /// Sets (or removes if None) effect that occurs on entering state.
+    /// Entry effect of initial state is dispatched before first character
+    /// - state: entered state,
+    /// - effect: side effect
+    pub fn set_entry_effect<'a>(
+        &mut self, 
+        state: State, 
+        effect: Option<Effect>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        Self::set_state_effect(&self.transition_table, &mut self.entry_effects, state, effect)
+    }
+
+    /// Sets (or removes if None) effect that occurs on leaving state.
+    /// Exit effect of last state is dispatched after last character
+    /// (before post-effect)
+    /// - state: left state,
+    /// - effect: side effect
+    pub fn set_exit_effect<'a>(
+        &mut self, 
+        state: State, 
+        effect: Option<Effect>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        Self::set_state_effect(&self.transition_table, &mut self.exit_effects, state, effect)
+    }
+
+    /// Returns composite state that state is nested into
+    /// - state: nested state
+    pub fn parent(&self, state: State) -> Option<State> {
+        self.hierarchy.parent(state)
+    }
+
+    /// Checks whether FSM has nested states or history pseudo-states
+    pub fn is_hierarchical(&self) -> bool {
+        !self.hierarchy.is_flat()
+    }
+
+    /// Returns nesting of states
+    pub(crate) fn hierarchy(&self) -> &Hierarchy<State> {
+        &self.hierarchy
+    }
+
+    /// Nests state into composite state (statechart). 
+    /// Nested state inherits transitions of composite state:
+    /// they're tried after its own transitions (so they may be overridden).
+    /// On transition, states are left from innermost one up to common 
+    /// composite state and entered from outermost one down to target.
+    /// Moved state stops being initial child of its former composite state
+    /// - child: nested state,
+    /// - parent: composite state
+    pub fn set_parent<'a>(
+        &mut self, 
+        child: State, 
+        parent: State
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        for state in [child, parent].iter() {
+            if !self.transition_table.contains_key(state) {
+                return Err(FSMError::StateDoesNotExist(*state));
+            }
+        }
+
+        if self.hierarchy.is_within(parent, child) {
+            return Err(FSMError::CyclicHierarchy(child));
+        }
+
+        self.hierarchy.set_parent(child, parent);
+
+        Ok(())
+    }
+
+    /// Sets state that is entered when transition targets composite state
+    /// (composite state without initial child stays active itself)
+    /// - parent: composite state,
+    /// - child: initial nested state
+    pub fn set_initial_child<'a>(
+        &mut self, 
+        parent: State, 
+        child: State
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        if self.hierarchy.parent(child) != Some(parent) {
+            return Err(FSMError::NotChild { parent, child });
+        }
+
+        self.hierarchy.set_initial_child(parent, child);
+
+        Ok(())
+    }
+
+    /// Adds history pseudo-state of composite state.
+    /// Transition to pseudo-state resumes state that was active 
+    /// when composite state was left last time (in current run), 
+    /// or enters composite state as usual if it wasn't left yet
+    /// - pseudo_state: target of transitions (it needs no transitions itself),
+    /// - parent: composite state,
+    /// - history: kind of history
+    pub fn add_history<'a>(
+        &mut self, 
+        pseudo_state: State, 
+        parent: State, 
+        history: History
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        if !self.transition_table.contains_key(&parent) {
+            return Err(FSMError::StateDoesNotExist(parent));
+        }
+
+        self.hierarchy.add_history(pseudo_state, parent, history);
+
+        Ok(())
+    }
+
+    /// Returns tests of registers that must hold if input ends in state
+    /// (or None if state has no end tests)
+    /// - state: last state
+    pub fn end_tests(&self, state: State) -> Option<&Vec<RegisterTest>> {
+        self.end_tests.get(&state)
+    }
+
+    /// Adds test of registers that must hold if input ends in state
+    /// (or in states nested into it), e.g. to reject input that ends
+    /// before repetition counted by register is complete.
+    /// Run fails with "RejectedEnd" before exit effects and post-effects
+    /// - state: last state,
+    /// - test: register index and bound
+    pub fn add_end_test<'a>(
+        &mut self, 
+        state: State, 
+        test: RegisterTest
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        if !self.transition_table.contains_key(&state) {
+            return Err(FSMError::StateDoesNotExist(state));
+        }
+
+        self.end_tests.entry(state).or_default().push(test);
+
+        Ok(())
+    }
+
+    fn set_state_effect<'a>(
+        transition_table: &Map<State, Vec<Transition<State, Effect, Context, Input>>>,
+        state_effects: &mut Map<State, Effect>,
+        state: State,
+        effect: Option<Effect>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        if !transition_table.contains_key(&state) {
+            return Err(FSMError::StateDoesNotExist(state));
+        }
+
+        match effect {
+            Some(effect) => state_effects.insert(state, effect),
+            None => state_effects.remove(&state)
+        };
+
+        Ok(())
+    }
+
     /// Merges effects into existing fsm for its states
-    /// aligned to order of transitions for each state
-    /// - effects_map: map from pair of states ("from", "to") to ordered list of effects
+    /// aligned to order of transitions for each state:
+    /// n-th list of effects is appended to effects of n-th transition
+    /// between pair of states, so merging several maps builds ordered effect lists
+    /// - effects_map: map from pair of states ("from", "to") to ordered lists of effects
+    ///   (one list per transition)
+    ///
     /// (This method is created mainly for testing, reusing the same states and 
     /// transition rules (i.e. partial fsm) for different effects configurations.
     /// For typical cases, 
     /// it's recommended to build fsm from its initialization (i.e. using "new" method))
     pub fn merge_effects<'a>(
         &mut self, 
-        effects_map: &HashMap<StatesConnection<State>, Vec<Effect>>
-    ) -> Result<(), FSMError<'a, State>> {
-        for (conn, effects) in effects_map.iter() {
+        effects_map: &Map<StatesConnection<State>, Vec<Vec<Effect>>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> {
+        for (conn, effect_lists) in effects_map.iter() {
             if !self.transition_table.contains_key(&conn.to) {
                 return Err(FSMError::StateDoesNotExist(conn.to));
             }
 
             match self.transition_table.get_mut(&conn.from) {
                 Some(transitions) => {
-                    let mut eff_counter: usize = 0;
+                    let trans_count = transitions.iter()
+                        .filter(|trans| trans.to == conn.to)
+                        .count();
 
-                    for trans in transitions.iter_mut() {
-                        if conn.to == trans.to {
-                            trans.effect = Some(effects[eff_counter]);
-                            eff_counter += 1;
-                        }
-                    }
-                    
-                    if eff_counter == 0 {
+                    // every list needs its own transition
+                    if trans_count == 0 || effect_lists.len() > trans_count {
                         return Err(FSMError::TransDoesNotExist(*conn));
                     }
+
+                    let aligned = transitions.iter_mut()
+                        .filter(|trans| trans.to == conn.to)
+                        .zip(effect_lists.iter());
+
+                    for (trans, effects) in aligned {
+                        trans.effects.extend(effects.iter().cloned());
+                    }
                 },
                 None => return Err(
                     FSMError::StateDoesNotExist(conn.from)
@@ -95,74 +381,598 @@ impl<State, Effect> FSM<State, Effect>
         Ok(())
     }
 
-    /// Runs some string through FSM to validate it (and apply some effects)
-    /// - string: runnable string,
+    /// Checks that bounds of registers are consistent:
+    /// tests of every transition may hold together,
+    /// registers that must be non-zero are incremented somewhere 
+    /// and updated registers are tested somewhere (end tests included).
+    /// Returns found issues (empty if there are none) ordered by kind
+    /// and then by state, transition and register
+    pub fn analyze_registers(&self) -> Vec<RegisterIssue<State>> 
+        where State: Ord
+    {
+        registers::analyze(&self.transition_table, &self.end_tests)
+    }
+
+    /// Runs items of some input through FSM like "proceed" does for characters
+    /// (e.g. events or byte tokens). Input data of effects holds empty string;
+    /// start and end effects get no item
+    /// - inputs: runnable items,
     /// - effector: module that mutates some data by effects
-    pub fn proceed<'a>(
+    pub fn proceed_iter<'a, I>(
+        &self, 
+        inputs: I,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> 
+        where I: IntoIterator<Item = Input>
+    {
+        self.run(&EMPTY_STRING, inputs.into_iter(), effector, |_| None, None, None)
+    }
+
+    /// Runs items of some input through FSM like "proceed_iter" does
+    /// and records every step of run into trace (see "proceed_traced")
+    /// - inputs: runnable items,
+    /// - effector: module that mutates some data by effects,
+    /// - trace: recorder of steps
+    pub fn proceed_iter_traced<'a, I>(
+        &self, 
+        inputs: I,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error, Input>>,
+        trace: &mut Trace<State, Effect, Input>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> 
+        where I: IntoIterator<Item = Input>
+    {
+        trace.start("");
+        self.run(&EMPTY_STRING, inputs.into_iter(), effector, |_| None, Some(trace), None)
+    }
+
+    /// Collects effects of single step in order of dispatch
+    /// (exit effects of left states, effects of transition, entry effects of entered states)
+    pub(crate) fn step_effects<'e>(
+        &'e self, 
+        left: impl Iterator<Item = State> + 'e, 
+        effects: &'e [Effect], 
+        entered: impl Iterator<Item = State> + 'e
+    ) -> impl Iterator<Item = &'e Effect> {
+        left.filter_map(move |state| self.exit_effect(state))
+            .chain(effects.iter())
+            .chain(entered.filter_map(move |state| self.entry_effect(state)))
+    }
+
+    /// Finds first transition that accepts input item among transitions of state 
+    /// and then of its composite states (innermost first).
+    /// Position of transition counts transitions of all visited states
+    pub(crate) fn match_transition<'t>(
+        &'t self,
+        state: State,
+        ch: Input,
+        context: Option<&Context>,
+        registers: &[u64]
+    ) -> Option<MatchedTransition<'t, State, Effect, Context, Input>> {
+        let accepts = |(trans_id, transition): (usize, &'t Transition<State, Effect, Context, Input>)| {
+            if !transition.accepts_registers(registers) {
+                return None;
+            }
+
+            match transition.transit_in(ch, context) {
+                (Some(new_state), effects) => Some((trans_id, transition, new_state, effects)),
+                _ => None
+            }
+        };
+
+        // flat FSM has nothing to inherit
+        if self.hierarchy.is_flat() {
+            return self.transition_table.get(&state)?.iter()
+                .enumerate()
+                .find_map(accepts);
+        }
+
+        self.hierarchy.ancestors(state)
+            .filter_map(|state| self.transition_table.get(&state))
+            .flatten()
+            .enumerate()
+            .find_map(accepts)
+    }
+
+    /// Returns state that run starts from (innermost state for composite initial state)
+    pub(crate) fn start_state(&self) -> State {
+        self.hierarchy.resolve(self.initial_state, &Map::new())
+    }
+
+    /// Moves from state by input item without any effects 
+    /// (guards never match, history isn't remembered)
+    /// - state: current state,
+    /// - ch: input item,
+    /// - registers: values of registers that are tested and updated
+    pub(crate) fn next_state(&self, state: State, ch: Input, registers: &mut Vec<u64>) -> Option<State> {
+        let (_, transition, target, _) = self.match_transition(state, ch, None, registers)?;
+
+        for update in transition.updates.iter() {
+            update.apply(registers);
+        }
+
+        Some(self.hierarchy.resolve(target, &Map::new()))
+    }
+
+    /// Moves from state by input item through every transition that accepts it
+    /// (not only the first one) like "next_state" does
+    /// - state: current state,
+    /// - ch: input item,
+    /// - registers: values of registers that are tested,
+    /// - visit: takes every target with registers updated by its transition
+    pub(crate) fn next_states(
+        &self, 
+        state: State, 
+        ch: Input, 
+        registers: &[u64], 
+        mut visit: impl FnMut(State, Vec<u64>)
+    ) {
+        let transitions = self.hierarchy.ancestors(state)
+            .filter_map(|state| self.transition_table.get(&state))
+            .flatten()
+            .filter(|transition| transition.accepts_registers(registers));
+
+        for transition in transitions {
+            if let (Some(target), _) = transition.transit(ch) {
+                let mut updated = registers.to_vec();
+
+                for update in transition.updates.iter() {
+                    update.apply(&mut updated);
+                }
+
+                visit(self.hierarchy.resolve(target, &Map::new()), updated);
+            }
+        }
+    }
+
+    /// Returns states that observer is notified about leaving
+    /// (unlike state effects, self-loops exit their state)
+    fn observed_exits(&self, from: State, to: State) -> impl Iterator<Item = State> + '_ {
+        let self_loop = if from == to { Some(from) } else { None };
+
+        self.hierarchy.transfer(from, to).0.chain(self_loop)
+    }
+
+    /// Runs input items with effector that may also provide context for guards
+    /// (context is read between dispatches, so it reflects every applied effect).
+    /// String is input of run for characters (or empty for other items)
+    fn run<'a, E, I>(
         &self, 
         string: &'a String,
-        mut effector: Option<&'a mut dyn Effector<Effect>>
-    ) -> Result<(), FSMError<'a, State>> 
+        inputs: I,
+        mut effector: Option<&'a mut E>,
+        context: fn(&E) -> Option<&Context>,
+        mut trace: Option<&mut Trace<State, Effect, Input>>,
+        mut observer: Option<&mut dyn Observer<State, Input>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> 
+        where E: Effector<Effect, State, Error, Input> + ?Sized,
+              I: Iterator<Item = Input>
     {
-        let mut curr_state = self.initial_state;
-        let mut char_id: usize = 0;
+        let mut cursor = self.start(string, &mut effector, &mut trace, &mut observer)?;
 
-        for ch in string.chars() {
-            match self.transition_table.get(&curr_state) {
-                Some(transitions) => {
-                    let mut accepted = false;
-                    
-                    for transition in transitions.iter() {
-                        match transition.transit(ch) {
-                            (Some(new_state), effect) => {
-                                curr_state = new_state;
-                                accepted = true;
-                                
-                                if let (Some(effector), Some(effect)) = 
-                                    (effector.as_mut(), effect) 
-                                {
-                                    effector.dispatch(effect, StreamData {
-                                        string,
-                                        index: char_id,
-                                        character: ch
-                                    });    
-                                }
-
-                                break;
-                            },
-                            _ => {}
-                        }
+        for (char_id, ch) in inputs.enumerate() {
+            if cursor.is_finished {
+                return Ok(());
+            }
+
+            self.step(
+                &mut cursor, 
+                string,
+                char_id,
+                ch,
+                &mut effector, 
+                context, 
+                &mut trace, 
+                &mut observer
+            )?;
+        }
+
+        if cursor.is_finished {
+            return Ok(());
+        }
+
+        self.end(&mut cursor, string, &mut effector, &mut trace, &mut observer)
+    }
+
+    /// Enters initial state (with its composite states) before first item
+    pub(crate) fn start<'a, E>(
+        &self, 
+        string: &'a String,
+        effector: &mut Option<&mut E>,
+        trace: &mut Option<&mut Trace<State, Effect, Input>>,
+        observer: &mut Option<&mut dyn Observer<State, Input>>
+    ) -> Result<Cursor<State>, FSMError<'a, State, Error, Input>> 
+        where E: Effector<Effect, State, Error, Input> + ?Sized
+    {
+        let mut cursor = Cursor {
+            state: self.initial_state,
+            length: 0,
+            registers: Vec::new(),
+            remembered: Map::new(),
+            is_finished: false
+        };
+        cursor.state = self.hierarchy.resolve(self.initial_state, &cursor.remembered);
+
+        let mut path: Vec<State> = self.hierarchy.ancestors(cursor.state).collect();
+        path.reverse();
+
+        if let Some(observer) = observer.as_mut() {
+            for state in path.iter() {
+                observer.on_enter(*state);
+            }
+        }
+
+        let mut start_effects = Vec::new();
+        let control = dispatch_all(
+            effector,
+            path.iter().filter_map(|state| self.entry_effect(*state)),
+            StreamData {
+                string,
+                index: 0,
+                character: None
+            },
+            trace.as_ref().map(|_| &mut start_effects)
+        );
+
+        if let Some(trace) = trace.as_mut() {
+            for effect in start_effects {
+                trace.record_start(effect);
+            }
+        }
+
+        match control {
+            Control::Continue => {},
+            Control::Stop => Self::finish(&mut cursor, observer),
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(state) => {
+                let initial_state = cursor.state;
+                cursor.state = self.target(state, &cursor.remembered)?;
+
+                if let Some(observer) = observer.as_mut() {
+                    let (left, entered) = self.hierarchy.transfer(initial_state, cursor.state);
+
+                    for state in left {
+                        observer.on_exit(state);
                     }
 
-                    if !accepted {
-                        return Err(FSMError::NoValidTransition {
-                            from: curr_state,
-                            input_data: StreamData {
-                                string,
-                                index: char_id,
-                                character: ch
-                            }
-                        });
+                    for state in entered {
+                        observer.on_enter(state);
                     }
-                },
-                None => {
-                    return Err(FSMError::StateDoesNotExist(curr_state));
                 }
             }
+        }
+
+        Ok(cursor)
+    }
+
+    /// Proceeds single input item
+    /// - index: position of item,
+    /// - ch: item
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn step<'a, 'd, E>(
+        &self, 
+        cursor: &mut Cursor<State>,
+        string: &'a String,
+        index: usize,
+        ch: Input,
+        effector: &mut Option<&'d mut E>,
+        context: fn(&E) -> Option<&Context>,
+        trace: &mut Option<&mut Trace<State, Effect, Input>>,
+        observer: &mut Option<&mut dyn Observer<State, Input>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> 
+        where E: Effector<Effect, State, Error, Input> + ?Sized
+    {
+        let from = cursor.state;
+        let input_data = StreamData {
+            string,
+            index,
+            character: Some(ch)
+        };
+        cursor.length = index + 1;
+
+        let context = effector.as_deref().and_then(context);
+        let matched = self.match_transition(from, ch, context, &cursor.registers);
+
+        let (trans_id, transition, target, effects) = match matched {
+            Some(matched) => matched,
+            // nested states always have own transitions (see "set_parent"),
+            // so state without them matches nothing
+            None if !self.transition_table.contains_key(&from) => {
+                return Err(FSMError::StateDoesNotExist(from));
+            },
+            None => {
+                if let Some(observer) = observer.as_mut() {
+                    observer.on_reject(from, &input_data);
+                }
+
+                return Err(FSMError::NoValidTransition {
+                    from,
+                    input_data
+                });
+            }
+        };
+
+        for update in transition.updates.iter() {
+            update.apply(&mut cursor.registers);
+        }
+
+        let new_state = self.hierarchy.resolve(target, &cursor.remembered);
+
+        let mut exited = Vec::new();
+
+        if let Some(observer) = observer.as_mut() {
+            exited.extend(self.observed_exits(from, new_state));
+
+            for state in exited.iter() {
+                observer.on_exit(*state);
+            }
 
-            char_id += 1;
+            observer.on_transition(from, new_state, &input_data);
         }
 
-        if let (Some(effector), Some(effect)) = 
-            (effector, self.post_effect) 
-        {
-            effector.dispatch(effect, StreamData {
+        let mut step_effects = Vec::new();
+        let control = if self.hierarchy.is_flat() {
+            // flat FSM leaves and enters only states of transition (if they differ)
+            let moved = Some(new_state).filter(|state| *state != from);
+
+            dispatch_all(
+                effector,
+                self.step_effects(moved.map(|_| from).into_iter(), effects, moved.into_iter()),
+                input_data,
+                trace.as_ref().map(|_| &mut step_effects)
+            )
+        } else {
+            let (left, entered) = self.hierarchy.transfer(from, new_state);
+
+            // composite states remember their innermost state for history
+            for state in left.clone().skip(1) {
+                cursor.remembered.insert(state, from);
+            }
+
+            dispatch_all(
+                effector,
+                self.step_effects(left, effects, entered),
+                input_data,
+                trace.as_ref().map(|_| &mut step_effects)
+            )
+        };
+
+        cursor.state = match control {
+            Control::GoTo(state) => self.target(state, &cursor.remembered)?,
+            _ => new_state
+        };
+
+        if let Some(trace) = trace.as_mut() {
+            trace.record(TraceStep {
+                index,
+                character: ch,
+                from,
+                transition: trans_id,
+                to: cursor.state,
+                effects: step_effects
+            });
+        }
+
+        if let Some(observer) = observer.as_mut() {
+            // redirection by effector may leave more states than transition did
+            for state in self.observed_exits(from, cursor.state) {
+                if !exited.contains(&state) {
+                    observer.on_exit(state);
+                    exited.push(state);
+                }
+            }
+
+            let mut entered: Vec<State> = self.hierarchy.ancestors(cursor.state)
+                .filter(|state| !self.hierarchy.is_within(from, *state) || exited.contains(state))
+                .collect();
+            entered.reverse();
+
+            for state in entered {
+                observer.on_enter(state);
+            }
+        }
+
+        match control {
+            Control::Stop => Self::finish(cursor, observer),
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Leaves last state (with its composite states) after last item
+    pub(crate) fn end<'a, E>(
+        &self, 
+        cursor: &mut Cursor<State>,
+        string: &'a String,
+        effector: &mut Option<&mut E>,
+        trace: &mut Option<&mut Trace<State, Effect, Input>>,
+        observer: &mut Option<&mut dyn Observer<State, Input>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>> 
+        where E: Effector<Effect, State, Error, Input> + ?Sized
+    {
+        // position after last byte of string or after last item
+        let index = string.len().max(cursor.length);
+
+        let is_accepted = self.hierarchy.ancestors(cursor.state)
+            .filter_map(|state| self.end_tests.get(&state))
+            .flatten()
+            .all(|test| test.holds(register_value(&cursor.registers, test.register())));
+
+        if !is_accepted {
+            return Err(FSMError::RejectedEnd {
+                state: cursor.state,
+                index
+            });
+        }
+
+        let mut end_effects = Vec::new();
+        let control = dispatch_all(
+            effector,
+            self.hierarchy.ancestors(cursor.state)
+                .filter_map(|state| self.exit_effect(state))
+                .chain(self.post_effects.iter()),
+            StreamData {
                 string,
-                index: string.len(),
-                character: '\0'
-            })
+                index,
+                character: None
+            },
+            trace.as_ref().map(|_| &mut end_effects)
+        );
+
+        if let Some(trace) = trace.as_mut() {
+            for effect in end_effects {
+                trace.record_end(effect);
+            }
+        }
+
+        match control {
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(state) => cursor.state = self.target(state, &cursor.remembered)?,
+            _ => {}
         }
 
+        Self::finish(cursor, observer);
+
         Ok(())
     }
+
+    /// Resolves state that effector goes to (like transition does)
+    /// and checks that it belongs to FSM
+    /// - state: next state,
+    /// - remembered: innermost states of composite states (for history)
+    pub(crate) fn target<'a>(
+        &self, 
+        state: State, 
+        remembered: &Map<State, State>
+    ) -> Result<State, FSMError<'a, State, Error, Input>> {
+        let resolved = self.hierarchy.resolve(state, remembered);
+
+        if self.transition_table.contains_key(&resolved) {
+            Ok(resolved)
+        } else {
+            Err(FSMError::StateDoesNotExist(resolved))
+        }
+    }
+
+    fn finish(
+        cursor: &mut Cursor<State>, 
+        observer: &mut Option<&mut dyn Observer<State, Input>>
+    ) {
+        cursor.is_finished = true;
+
+        if let Some(observer) = observer.as_mut() {
+            observer.on_finish(cursor.state);
+        }
+    }
+}
+
+/// Dispatches effects in order until effector returns 
+/// anything but "Continue" (all effects are dispatched if there's no effector).
+/// Dispatched effects are appended to "dispatched" list
+pub(crate) fn dispatch_all<'a, 'd, 'e, State, Effect, Error, Input, E>(
+    effector: &mut Option<&'d mut E>,
+    effects: impl Iterator<Item = &'e Effect>,
+    input_data: StreamData<'a, Input>,
+    mut dispatched: Option<&mut Vec<Effect>>
+) -> Control<State, Error> 
+    where Effect: Clone + 'e,
+          Input: Copy,
+          E: Effector<Effect, State, Error, Input> + ?Sized
+{
+    for effect in effects {
+        if let Some(dispatched) = dispatched.as_mut() {
+            dispatched.push(effect.clone());
+        }
+
+        if let Some(effector) = effector.as_mut() {
+            match effector.dispatch(effect.clone(), input_data) {
+                Control::Continue => {},
+                control => return control
+            }
+        }
+    }
+
+    Control::Continue
+}
+
+/// Runs through strings (for FSM over characters)
+impl<State, Effect, Error, Context> FSM<State, Effect, Error, Context> 
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone,
+{
+    /// Runs some string through FSM to validate it (and apply some effects).
+    /// Effects are dispatched in order: 
+    /// entry effect of initial state (before first character), 
+    /// then for every character - exit effect of current state, 
+    /// effects of matched transition, entry effect of next state
+    /// (state effects aren't dispatched for self-loops as state isn't left),
+    /// then exit effect of last state and post-effects (after last character).
+    /// For nested states, exit and entry effects of every left and entered
+    /// composite state are dispatched too (see "set_parent").
+    /// Effector controls run by returned value (see "Control").
+    /// Guarded transitions never match as there's no context
+    /// - string: runnable string,
+    /// - effector: module that mutates some data by effects
+    pub fn proceed<'a>(
+        &self, 
+        string: &'a String,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<(), FSMError<'a, State, Error>> 
+    {
+        self.run(string, string.chars(), effector, |_| None, None, None)
+    }
+
+    /// Runs some string through FSM like "proceed" does
+    /// with context that is both effector and data read by guards
+    /// (so guards see every change made by effects of previous steps)
+    /// - string: runnable string,
+    /// - context: module that mutates itself by effects
+    pub fn proceed_in_context<'a>(
+        &self, 
+        string: &'a String,
+        context: &'a mut Context
+    ) -> Result<(), FSMError<'a, State, Error>> 
+        where Context: Effector<Effect, State, Error>
+    {
+        self.run(string, string.chars(), Some(context), |context| Some(context), None, None)
+    }
+
+    /// Runs some string through FSM like "proceed" does 
+    /// and records every step of run into trace
+    /// (previous content of trace is dropped).
+    /// Effects are recorded even if effector is None
+    /// - string: runnable string,
+    /// - effector: module that mutates some data by effects,
+    /// - trace: recorder of steps
+    pub fn proceed_traced<'a>(
+        &self, 
+        string: &'a String,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error>>,
+        trace: &mut Trace<State, Effect>
+    ) -> Result<(), FSMError<'a, State, Error>> 
+    {
+        trace.start(string);
+        self.run(string, string.chars(), effector, |_| None, Some(trace), None)
+    }
+
+    /// Runs some string through FSM like "proceed" does 
+    /// and notifies observer about every visited state and transition
+    /// (regardless of effects).
+    /// For each character hooks are called in order: 
+    /// "on_exit" of current state, "on_transition", 
+    /// dispatch of effects, "on_enter" of next state
+    /// (unlike state effects, self-loops exit and enter their state)
+    /// - string: runnable string,
+    /// - effector: module that mutates some data by effects,
+    /// - observer: listener of run
+    pub fn proceed_observed<'a>(
+        &self, 
+        string: &'a String,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error>>,
+        observer: &mut dyn Observer<State>
+    ) -> Result<(), FSMError<'a, State, Error>> 
+    {
+        self.run(string, string.chars(), effector, |_| None, None, Some(observer))
+    }
 }
@@ -0,0 +1,173 @@
+use core::hash::Hash;
+use crate::map::Map;
+
+/// Kind of history pseudo-state of composite state
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum History {
+    /// Resumes direct child of composite state that was active
+    /// when it was left (then descends to initial children)
+    Shallow,
+    /// Resumes innermost state that was active when composite state was left
+    Deep
+}
+
+/// Nesting of states of FSM (statechart)
+pub(crate) struct Hierarchy<State>
+    where State: Eq + PartialEq + Copy + Hash
+{
+    /// Composite state of every nested state
+    parents: Map<State, State>,
+    /// State entered when transition targets composite state
+    initial_children: Map<State, State>,
+    /// History pseudo-states with their composite states
+    histories: Map<State, (State, History)>
+}
+
+/// State and its composite states, innermost first (see "Hierarchy::ancestors").
+/// Composite state is looked up only when iteration reaches it
+#[derive(Clone)]
+pub(crate) struct Ancestors<'h, State>
+    where State: Eq + PartialEq + Copy + Hash
+{
+    hierarchy: &'h Hierarchy<State>,
+    /// State itself (until it's returned)
+    first: Option<State>,
+    /// Last returned state
+    last: Option<State>
+}
+
+impl<State> Hierarchy<State>
+    where State: Eq + PartialEq + Copy + Hash
+{
+    pub fn new() -> Self {
+        Self {
+            parents: Map::new(),
+            initial_children: Map::new(),
+            histories: Map::new()
+        }
+    }
+
+    /// Checks whether any state is nested
+    pub fn is_flat(&self) -> bool {
+        self.parents.is_empty() && self.histories.is_empty()
+    }
+
+    pub fn parent(&self, state: State) -> Option<State> {
+        self.parents.get(&state).copied()
+    }
+
+    /// Nests state (former composite state loses it as initial child)
+    pub fn set_parent(&mut self, child: State, parent: State) {
+        if let Some(former) = self.parents.insert(child, parent) {
+            if former != parent && self.initial_child(former) == Some(child) {
+                self.initial_children.remove(&former);
+            }
+        }
+    }
+
+    pub fn initial_child(&self, state: State) -> Option<State> {
+        self.initial_children.get(&state).copied()
+    }
+
+    pub fn set_initial_child(&mut self, parent: State, child: State) {
+        self.initial_children.insert(parent, child);
+    }
+
+    pub fn history(&self, pseudo_state: State) -> Option<(State, History)> {
+        self.histories.get(&pseudo_state).copied()
+    }
+
+    /// Returns all history pseudo-states (in arbitrary order)
+    pub fn history_states(&self) -> impl Iterator<Item = State> + '_ {
+        self.histories.keys().copied()
+    }
+
+    pub fn add_history(&mut self, pseudo_state: State, parent: State, history: History) {
+        self.histories.insert(pseudo_state, (parent, history));
+    }
+
+    /// Returns state and its composite states (innermost first) without allocating
+    pub fn ancestors(&self, state: State) -> Ancestors<'_, State> {
+        Ancestors {
+            hierarchy: self,
+            first: Some(state),
+            last: None
+        }
+    }
+
+    /// Checks whether state is nested into composite state (or is the same state)
+    pub fn is_within(&self, state: State, composite: State) -> bool {
+        self.ancestors(state).any(|ancestor| ancestor == composite)
+    }
+
+    /// Resolves target of transition into innermost state that becomes active:
+    /// history pseudo-state is replaced by remembered state of its composite state,
+    /// composite state descends to its initial children
+    /// - target: target of transition,
+    /// - remembered: last active innermost state of every left composite state
+    pub fn resolve(&self, target: State, remembered: &Map<State, State>) -> State {
+        // initial children and history states need nested states
+        if self.is_flat() {
+            return target;
+        }
+
+        let mut state = match self.history(target) {
+            Some((parent, history)) => match remembered.get(&parent) {
+                Some(&leaf) => match history {
+                    History::Deep => return leaf,
+                    History::Shallow => self.ancestors(leaf)
+                        .find(|state| self.parent(*state) == Some(parent))
+                        .unwrap_or(parent)
+                },
+                None => parent
+            },
+            None => target
+        };
+
+        // descent can't be longer than number of initial children,
+        // so it stops instead of looping forever
+        for _ in 0..self.initial_children.len() {
+            match self.initial_child(state) {
+                Some(child) => state = child,
+                None => break
+            }
+        }
+
+        state
+    }
+
+    /// Returns states that are left (innermost first) and entered (outermost first)
+    /// by moving from one innermost state to another one (without allocating).
+    /// Nothing is left or entered if states are the same (self-loop)
+    pub fn transfer(
+        &self, 
+        from: State, 
+        to: State
+    ) -> (impl Iterator<Item = State> + Clone + '_, impl Iterator<Item = State> + Clone + '_) {
+        let left = self.ancestors(from)
+            .take_while(move |state| !self.is_within(to, *state));
+        let entered_count = self.ancestors(to)
+            .take_while(|state| !self.is_within(from, *state))
+            .count();
+        let entered = (0..entered_count).rev()
+            .filter_map(move |depth| self.ancestors(to).nth(depth));
+
+        (left, entered)
+    }
+}
+
+impl<'h, State> Iterator for Ancestors<'h, State>
+    where State: Eq + PartialEq + Copy + Hash
+{
+    type Item = State;
+
+    fn next(&mut self) -> Option<State> {
+        // cycles are rejected by "FSM::set_parent"
+        self.last = match self.first.take() {
+            Some(state) => Some(state),
+            None => self.hierarchy.parent(self.last?)
+        };
+
+        self.last
+    }
+}
@@ -0,0 +1,259 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::map::Map;
+use crate::types::Transition;
+use crate::fsm::{FSM, FSMError};
+
+/// Builder of keyword detector: FSM (Aho–Corasick automaton)
+/// that dispatches effect of every literal pattern at position of its last character.
+/// States of FSM are nodes of trie of patterns (initial state is 0).
+/// Detector never rejects input
+pub struct Keywords<Effect>
+    where Effect: Clone
+{
+    /// Patterns with their effects (in order of addition)
+    patterns: Vec<(Vec<char>, Effect)>,
+    is_case_insensitive: bool,
+    is_overlapping: bool
+}
+
+/// Trie of patterns with failure links
+struct Trie {
+    /// Children of every node (in order of insertion)
+    children: Vec<Vec<(char, usize)>>,
+    /// Node of the longest proper suffix that is also in trie
+    failures: Vec<usize>,
+    /// Patterns that end in node (including ones reached by failure links),
+    /// the longest ones first
+    outputs: Vec<Vec<usize>>,
+    /// Nodes in breadth-first order
+    order: Vec<usize>
+}
+
+impl<Effect> Keywords<Effect>
+    where Effect: Clone
+{
+    /// Creates builder without patterns
+    /// (case-sensitive, matches don't overlap)
+    pub fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+            is_case_insensitive: false,
+            is_overlapping: false
+        }
+    }
+
+    /// Adds literal pattern.
+    /// Empty pattern never matches
+    /// - pattern: text of pattern,
+    /// - effect: effect dispatched at every match of pattern
+    pub fn pattern(mut self, pattern: &str, effect: Effect) -> Self {
+        self.patterns.push((pattern.chars().collect(), effect));
+        self
+    }
+
+    /// Sets whether letters of patterns match letters of other case
+    /// (for letters with single-character lowercase and uppercase forms)
+    /// - is_case_insensitive: flag
+    pub fn case_insensitive(mut self, is_case_insensitive: bool) -> Self {
+        self.is_case_insensitive = is_case_insensitive;
+        self
+    }
+
+    /// Sets whether matches may overlap.
+    /// If they may then every pattern that ends at some position is reported
+    /// (the longest ones first). Otherwise only the longest pattern that ends
+    /// the earliest is reported and search goes on after its end
+    /// - is_overlapping: flag
+    pub fn overlapping(mut self, is_overlapping: bool) -> Self {
+        self.is_overlapping = is_overlapping;
+        self
+    }
+
+    /// Compiles patterns into FSM
+    pub fn build<'a>(&self) -> Result<FSM<usize, Effect>, FSMError<'a, usize>> {
+        let trie = self.trie();
+        let mut targets: Vec<Map<char, usize>> = vec![Map::new(); trie.children.len()];
+        let mut transition_table = Map::new();
+
+        for &node in trie.order.iter() {
+            let mut node_targets = if node == 0 {
+                Map::new()
+            } else {
+                targets[trie.failures[node]].clone()
+            };
+
+            for &(ch, child) in trie.children[node].iter() {
+                node_targets.insert(ch, child);
+            }
+
+            let mut symbols: Vec<(char, usize)> = node_targets.iter()
+                .map(|(ch, target)| (*ch, *target))
+                .collect();
+            symbols.sort_unstable();
+
+            let mut transitions = Vec::new();
+
+            for (ch, target) in symbols {
+                let effects = self.effects(&trie, target);
+                let to = if !self.is_overlapping && !effects.is_empty() {
+                    0
+                } else {
+                    target
+                };
+
+                for variant in self.variants(ch) {
+                    transitions.push(Transition::literal(to, variant, effects.clone()));
+                }
+            }
+
+            transitions.push(Transition::new(0, None, None));
+
+            transition_table.insert(node, transitions);
+            targets[node] = node_targets;
+        }
+
+        FSM::new(0, transition_table, None)
+    }
+
+    /// Builds trie of patterns and resolves its failure links
+    fn trie(&self) -> Trie {
+        let mut children: Vec<Vec<(char, usize)>> = vec![Vec::new()];
+        let mut own_outputs: Vec<Vec<usize>> = vec![Vec::new()];
+
+        for (pattern_id, (pattern, _)) in self.patterns.iter().enumerate() {
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut node = 0;
+
+            for &ch in pattern.iter() {
+                let ch = self.fold(ch);
+
+                node = match children[node].iter().find(|(symbol, _)| *symbol == ch) {
+                    Some(&(_, child)) => child,
+                    None => {
+                        children.push(Vec::new());
+                        own_outputs.push(Vec::new());
+
+                        let child = children.len() - 1;
+                        children[node].push((ch, child));
+                        child
+                    }
+                };
+            }
+
+            own_outputs[node].push(pattern_id);
+        }
+
+        let mut failures = vec![0; children.len()];
+        let mut outputs = own_outputs.clone();
+        let mut order = Vec::with_capacity(children.len());
+        let mut queue = VecDeque::new();
+
+        queue.push_back(0);
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+
+            for &(ch, child) in children[node].iter() {
+                if node != 0 {
+                    let mut failure = failures[node];
+
+                    loop {
+                        if let Some(&(_, next)) = children[failure].iter().find(|(symbol, _)| *symbol == ch) {
+                            failures[child] = next;
+                            break;
+                        }
+
+                        if failure == 0 {
+                            break;
+                        }
+
+                        failure = failures[failure];
+                    }
+
+                    let inherited = outputs[failures[child]].clone();
+                    outputs[child].extend(inherited);
+                }
+
+                queue.push_back(child);
+            }
+        }
+
+        Trie {
+            children,
+            failures,
+            outputs,
+            order
+        }
+    }
+
+    /// Returns effects of patterns that are matched on entering node
+    /// - trie: trie of patterns,
+    /// - node: entered node
+    fn effects(&self, trie: &Trie, node: usize) -> Vec<Effect> {
+        let outputs = &trie.outputs[node];
+
+        let reported = if self.is_overlapping {
+            outputs.len()
+        } else {
+            let longest = outputs.first().map_or(0, |&pattern_id| self.patterns[pattern_id].0.len());
+
+            outputs.iter()
+                .take_while(|&&pattern_id| self.patterns[pattern_id].0.len() == longest)
+                .count()
+        };
+
+        outputs.iter()
+            .take(reported)
+            .map(|&pattern_id| self.patterns[pattern_id].1.clone())
+            .collect()
+    }
+
+    /// Returns canonical form of character in trie
+    /// - ch: character of pattern
+    fn fold(&self, ch: char) -> char {
+        if self.is_case_insensitive {
+            single(ch.to_lowercase()).unwrap_or(ch)
+        } else {
+            ch
+        }
+    }
+
+    /// Returns characters of input that match character of trie
+    /// - ch: canonical character
+    fn variants(&self, ch: char) -> Vec<char> {
+        let mut variants = vec![ch];
+
+        if self.is_case_insensitive {
+            if let Some(upper) = single(ch.to_uppercase()) {
+                if upper != ch {
+                    variants.push(upper);
+                }
+            }
+        }
+
+        variants
+    }
+}
+
+impl<Effect> Default for Keywords<Effect>
+    where Effect: Clone
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the only character of case mapping
+/// (or None if mapping has several characters)
+/// - chars: case mapping
+fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None
+    }
+}
@@ -0,0 +1,146 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::ops::Range;
+use crate::map::Map;
+use crate::fsm::{FSM, FSMError};
+use crate::search::{MatchKind, match_prefix};
+
+/// Tokenizer built on FSM: every token is the longest prefix of rest of input
+/// that leads FSM from its initial state to accepting state
+/// (maximal munch with backtracking to the last accepting position).
+/// Effects of FSM aren't dispatched
+pub struct Lexer<State, Kind, Effect = ()>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    /// Recognizer of tokens
+    fsm: FSM<State, Effect>,
+    /// Kinds of tokens that end in accepting states
+    accepting: Map<State, Kind>
+}
+
+/// Piece of input recognized by lexer
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Token<'s, Kind> {
+    /// Kind of token (None for error token, i.e. input that no token matches)
+    pub kind: Option<Kind>,
+    /// Byte range of token in input
+    pub span: Range<usize>,
+    /// Text of token
+    pub text: &'s str
+}
+
+/// Iterator over tokens of some input (see "Lexer::tokens")
+pub struct Tokens<'l, 's, State, Kind, Effect>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    lexer: &'l Lexer<State, Kind, Effect>,
+    source: &'s str,
+    /// Byte position of next token
+    position: usize
+}
+
+impl<State, Kind, Effect> Lexer<State, Kind, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Kind: Copy,
+          Effect: Clone
+{
+    /// Creates new lexer
+    /// - fsm: recognizer of tokens,
+    /// - accepting: map from accepting state to kind of token
+    pub fn new<'a>(
+        fsm: FSM<State, Effect>,
+        accepting: Map<State, Kind>
+    ) -> Result<Self, FSMError<'a, State>> {
+        for state in accepting.keys() {
+            if fsm.transitions(*state).is_none() {
+                return Err(FSMError::StateDoesNotExist(*state));
+            }
+        }
+
+        Ok(Self {
+            fsm,
+            accepting
+        })
+    }
+
+    /// Returns recognizer of tokens
+    pub fn fsm(&self) -> &FSM<State, Effect> {
+        &self.fsm
+    }
+
+    /// Returns kind of tokens that end in state
+    /// (or None if state isn't accepting)
+    /// - state: state of FSM
+    pub fn kind(&self, state: State) -> Option<Kind> {
+        self.accepting.get(&state).copied()
+    }
+
+    /// Splits input into tokens.
+    /// Consecutive characters that don't start any token
+    /// are joined into single error token
+    /// - source: input
+    pub fn tokens<'l, 's>(&'l self, source: &'s str) -> Tokens<'l, 's, State, Kind, Effect> {
+        Tokens {
+            lexer: self,
+            source,
+            position: 0
+        }
+    }
+
+    /// Finds the longest non-empty token at the beginning of input
+    /// (returns its length in bytes and its kind)
+    /// - input: rest of input
+    fn longest_match(&self, input: &str) -> Option<(usize, Kind)> {
+        let (length, state) = match_prefix(
+            &self.fsm,
+            input,
+            |state| self.accepting.contains_key(&state),
+            MatchKind::LeftmostFirst
+        )?;
+
+        self.kind(state).map(|kind| (length, kind))
+    }
+}
+
+impl<'l, 's, State, Kind, Effect> Iterator for Tokens<'l, 's, State, Kind, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Kind: Copy,
+          Effect: Clone
+{
+    type Item = Token<'s, Kind>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+
+        if start >= self.source.len() {
+            return None;
+        }
+
+        let (end, kind) = match self.lexer.longest_match(&self.source[start..]) {
+            Some((length, kind)) => (start + length, Some(kind)),
+            None => {
+                let mut end = start;
+
+                for (position, ch) in self.source[start..].char_indices() {
+                    if position > 0 && self.lexer.longest_match(&self.source[start + position..]).is_some() {
+                        break;
+                    }
+
+                    end = start + position + ch.len_utf8();
+                }
+
+                (end, None)
+            }
+        };
+
+        self.position = end;
+
+        Some(Token {
+            kind,
+            span: start..end,
+            text: &self.source[start..end]
+        })
+    }
+}
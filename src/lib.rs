@@ -1,8 +1,51 @@
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
+extern crate alloc;
+
+pub mod map;
 pub mod types;
 pub mod fsm;
+pub mod vocabulary;
+pub mod scxml;
+pub mod trace;
+pub mod effectors;
+pub mod registers;
+pub mod pda;
+pub mod hierarchy;
+pub mod parallel;
+pub mod lexer;
+pub mod search;
+pub mod keywords;
+pub mod compiled;
+pub mod codegen;
+pub mod byte_dfa;
+pub mod runner;
+pub mod static_fsm;
+#[cfg(feature = "async")]
+pub mod stream;
 #[macro_use]
 pub mod macros;
+#[cfg(test)]
 mod tests;
 
-pub use types::{Predicate, Transition, Effector, StatesConnection};
+pub use map::{Map, Set};
+pub use types::{Predicate, Guard, Transition, Effector, Control, Observer, StatesConnection};
 pub use fsm::{FSM, FSMError};
+pub use vocabulary::Vocabulary;
+pub use scxml::{ScxmlVocabulary, ScxmlError};
+pub use trace::{Trace, TraceStep, TraceDiff, TraceError};
+pub use effectors::{HandlerEffector, FanOut};
+pub use registers::{RegisterTest, RegisterUpdate, RegisterIssue};
+pub use pda::{PDA, PDATransition, StackOp};
+pub use hierarchy::History;
+pub use parallel::{Parallel, ParallelError};
+pub use lexer::{Lexer, Token};
+pub use search::{Searcher, MatchKind};
+pub use keywords::Keywords;
+pub use compiled::{CompiledFSM, CompileError};
+pub use codegen::{Codegen, CodegenError};
+pub use byte_dfa::ByteDFA;
+pub use runner::Runner;
+pub use static_fsm::{StaticFSM, StaticRow, StaticTransition, StateIndex};
+#[cfg(feature = "async")]
+pub use stream::{AsyncEffector, Utf8Chars, StreamError};
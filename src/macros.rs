@@ -1,11 +1,11 @@
-/// Simple macro for initialization of hashmap
+/// Simple macro for initialization of map (see "Map")
 #[macro_export]
 macro_rules! map {
     ($($key:expr => $val:expr),* $(,)?) => {
         {
-            use std::collections::HashMap;
+            use $crate::map::Map;
             
-            let mut map = HashMap::new();
+            let mut map = Map::new();
 
             $(
                 map.insert($key, $val);
@@ -0,0 +1,562 @@
+//! Maps and sets of machines.
+//! With "std" feature they are backed by hash tables of standard library.
+//! Without it (for targets that have only "alloc") they are backed by vectors of entries
+//! with linear search, which is fast enough for machines with dozens of states.
+//! Both ways they have the same API and need "Eq" and "Hash" of keys,
+//! so code that builds with one of them builds with another one too
+
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::iter::FromIterator;
+use core::ops::Index;
+
+#[cfg(feature = "std")]
+mod tables {
+    pub use std::collections::{HashMap as Map, HashSet as Set};
+    pub use std::collections::hash_map::{Entry, IntoIter};
+    pub use std::collections::hash_set::IntoIter as SetIntoIter;
+}
+
+#[cfg(not(feature = "std"))]
+mod tables {
+    use core::borrow::Borrow;
+    use core::mem;
+    use alloc::vec::{self, Vec};
+
+    /// Map with linear search (entries in order of insertion)
+    #[derive(Clone, Debug)]
+    pub struct Map<K, V> {
+        entries: Vec<(K, V)>
+    }
+
+    /// Set with linear search (items in order of insertion)
+    #[derive(Clone, Debug)]
+    pub struct Set<T> {
+        items: Vec<T>
+    }
+
+    /// Iterator that moves entries out of map
+    pub type IntoIter<K, V> = vec::IntoIter<(K, V)>;
+
+    /// Iterator that moves items out of set
+    pub type SetIntoIter<T> = vec::IntoIter<T>;
+
+    /// Entry of map for some key (see "Map::entry")
+    pub struct Entry<'m, K, V> {
+        map: &'m mut Map<K, V>,
+        key: K
+    }
+
+    impl<K, V> Map<K, V>
+        where K: Eq
+    {
+        /// Creates empty map
+        pub fn new() -> Self {
+            Self {
+                entries: Vec::new()
+            }
+        }
+
+        /// Returns number of entries
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        /// Checks whether there are no entries
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        /// Removes every entry
+        pub fn clear(&mut self) {
+            self.entries.clear();
+        }
+
+        /// Checks whether map has value for key
+        /// - key: key of value
+        pub fn contains_key<Q>(&self, key: &Q) -> bool
+            where K: Borrow<Q>,
+                  Q: Eq + ?Sized
+        {
+            self.position(key).is_some()
+        }
+
+        /// Returns value for key
+        /// - key: key of value
+        pub fn get<Q>(&self, key: &Q) -> Option<&V>
+            where K: Borrow<Q>,
+                  Q: Eq + ?Sized
+        {
+            self.position(key).map(|position| &self.entries[position].1)
+        }
+
+        /// Returns mutable value for key
+        /// - key: key of value
+        pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+            where K: Borrow<Q>,
+                  Q: Eq + ?Sized
+        {
+            self.position(key).map(move |position| &mut self.entries[position].1)
+        }
+
+        /// Sets value for key and returns previous value
+        /// - key: key of value,
+        /// - value: new value
+        pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+            match self.position(&key) {
+                Some(position) => Some(mem::replace(&mut self.entries[position].1, value)),
+                None => {
+                    self.entries.push((key, value));
+                    None
+                }
+            }
+        }
+
+        /// Removes value for key and returns it
+        /// - key: key of value
+        pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+            where K: Borrow<Q>,
+                  Q: Eq + ?Sized
+        {
+            self.position(key).map(|position| self.entries.remove(position).1)
+        }
+
+        /// Returns entry of map for key
+        /// - key: key of entry
+        pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+            Entry {
+                map: self,
+                key
+            }
+        }
+
+        /// Iterates over keys
+        pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+            self.entries.iter().map(|(key, _)| key)
+        }
+
+        /// Iterates over values
+        pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+            self.entries.iter().map(|(_, value)| value)
+        }
+
+        /// Iterates over entries
+        pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+            self.entries.iter().map(|(key, value)| (key, value))
+        }
+
+        /// Iterates over entries with mutable values
+        pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> + '_ {
+            self.entries.iter_mut().map(|(key, value)| (&*key, value))
+        }
+
+        /// Finds position of entry for key
+        /// - key: key of entry
+        fn position<Q>(&self, key: &Q) -> Option<usize>
+            where K: Borrow<Q>,
+                  Q: Eq + ?Sized
+        {
+            self.entries.iter().position(|(other, _)| other.borrow() == key)
+        }
+    }
+
+    impl<'m, K, V> Entry<'m, K, V>
+        where K: Eq
+    {
+        /// Returns value of entry (inserting value made by function if there's none)
+        /// - make: function that makes value
+        pub fn or_insert_with(self, make: impl FnOnce() -> V) -> &'m mut V {
+            let position = match self.map.position(&self.key) {
+                Some(position) => position,
+                None => {
+                    self.map.entries.push((self.key, make()));
+                    self.map.entries.len() - 1
+                }
+            };
+
+            &mut self.map.entries[position].1
+        }
+
+        /// Returns value of entry (inserting value if there's none)
+        /// - value: inserted value
+        pub fn or_insert(self, value: V) -> &'m mut V {
+            self.or_insert_with(|| value)
+        }
+
+        /// Returns value of entry (inserting default value if there's none)
+        pub fn or_default(self) -> &'m mut V
+            where V: Default
+        {
+            self.or_insert_with(V::default)
+        }
+    }
+
+    impl<K, V> IntoIterator for Map<K, V> {
+        type Item = (K, V);
+        type IntoIter = vec::IntoIter<(K, V)>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.entries.into_iter()
+        }
+    }
+
+    impl<T> Set<T>
+        where T: Eq
+    {
+        /// Creates empty set
+        pub fn new() -> Self {
+            Self {
+                items: Vec::new()
+            }
+        }
+
+        /// Returns number of items
+        pub fn len(&self) -> usize {
+            self.items.len()
+        }
+
+        /// Checks whether there are no items
+        pub fn is_empty(&self) -> bool {
+            self.items.is_empty()
+        }
+
+        /// Checks whether set has item
+        /// - item: checked item
+        pub fn contains<Q>(&self, item: &Q) -> bool
+            where T: Borrow<Q>,
+                  Q: Eq + ?Sized
+        {
+            self.items.iter().any(|other| other.borrow() == item)
+        }
+
+        /// Adds item and checks whether it's new
+        /// - item: added item
+        pub fn insert(&mut self, item: T) -> bool {
+            if self.contains(&item) {
+                return false;
+            }
+
+            self.items.push(item);
+            true
+        }
+
+        /// Iterates over items
+        pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+            self.items.iter()
+        }
+    }
+
+    impl<T> IntoIterator for Set<T> {
+        type Item = T;
+        type IntoIter = vec::IntoIter<T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.items.into_iter()
+        }
+    }
+}
+
+/// Map of machines (entries are in arbitrary order)
+#[derive(Clone, Debug)]
+pub struct Map<K, V> {
+    table: tables::Map<K, V>
+}
+
+/// Set of machines (items are in arbitrary order)
+#[derive(Clone, Debug)]
+pub struct Set<T> {
+    table: tables::Set<T>
+}
+
+/// Entry of map for some key (see "Map::entry")
+pub struct Entry<'m, K, V> {
+    entry: tables::Entry<'m, K, V>
+}
+
+/// Iterator that moves entries out of map
+pub struct IntoIter<K, V> {
+    iter: tables::IntoIter<K, V>
+}
+
+/// Iterator that moves items out of set
+pub struct SetIntoIter<T> {
+    iter: tables::SetIntoIter<T>
+}
+
+impl<K, V> Map<K, V>
+    where K: Eq + Hash
+{
+    /// Creates empty map
+    pub fn new() -> Self {
+        Self {
+            table: tables::Map::new()
+        }
+    }
+
+    /// Returns number of entries
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Checks whether there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Removes every entry
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    /// Checks whether map has value for key
+    /// - key: key of value
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.table.contains_key(key)
+    }
+
+    /// Returns value for key
+    /// - key: key of value
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.table.get(key)
+    }
+
+    /// Returns mutable value for key
+    /// - key: key of value
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.table.get_mut(key)
+    }
+
+    /// Sets value for key and returns previous value
+    /// - key: key of value,
+    /// - value: new value
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.table.insert(key, value)
+    }
+
+    /// Removes value for key and returns it
+    /// - key: key of value
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.table.remove(key)
+    }
+
+    /// Returns entry of map for key
+    /// - key: key of entry
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        Entry {
+            entry: self.table.entry(key)
+        }
+    }
+
+    /// Iterates over keys
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.table.keys()
+    }
+
+    /// Iterates over values
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.table.values()
+    }
+
+    /// Iterates over entries
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.table.iter()
+    }
+
+    /// Iterates over entries with mutable values
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> + '_ {
+        self.table.iter_mut()
+    }
+}
+
+impl<'m, K, V> Entry<'m, K, V>
+    where K: Eq + Hash
+{
+    /// Returns value of entry (inserting value made by function if there's none)
+    /// - make: function that makes value
+    pub fn or_insert_with(self, make: impl FnOnce() -> V) -> &'m mut V {
+        self.entry.or_insert_with(make)
+    }
+
+    /// Returns value of entry (inserting value if there's none)
+    /// - value: inserted value
+    pub fn or_insert(self, value: V) -> &'m mut V {
+        self.entry.or_insert(value)
+    }
+
+    /// Returns value of entry (inserting default value if there's none)
+    pub fn or_default(self) -> &'m mut V
+        where V: Default
+    {
+        self.entry.or_default()
+    }
+}
+
+impl<K, V> Default for Map<K, V>
+    where K: Eq + Hash
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for Map<K, V>
+    where K: Eq + Hash
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, entries: I) {
+        for (key, value) in entries {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Map<K, V>
+    where K: Eq + Hash
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let mut map = Self::new();
+        map.extend(entries);
+        map
+    }
+}
+
+impl<K, V, Q> Index<&Q> for Map<K, V>
+    where K: Eq + Hash + Borrow<Q>,
+          Q: Eq + Hash + ?Sized
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V> IntoIterator for Map<K, V>
+    where K: Eq + Hash
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.table.into_iter()
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> Set<T>
+    where T: Eq + Hash
+{
+    /// Creates empty set
+    pub fn new() -> Self {
+        Self {
+            table: tables::Set::new()
+        }
+    }
+
+    /// Returns number of items
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Checks whether there are no items
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Checks whether set has item
+    /// - item: checked item
+    pub fn contains<Q>(&self, item: &Q) -> bool
+        where T: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.table.contains(item)
+    }
+
+    /// Adds item and checks whether it's new
+    /// - item: added item
+    pub fn insert(&mut self, item: T) -> bool {
+        self.table.insert(item)
+    }
+
+    /// Iterates over items
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.table.iter()
+    }
+}
+
+impl<T> Default for Set<T>
+    where T: Eq + Hash
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for Set<T>
+    where T: Eq + Hash
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Set<T>
+    where T: Eq + Hash
+{
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut set = Self::new();
+        set.extend(items);
+        set
+    }
+}
+
+impl<T> IntoIterator for Set<T>
+    where T: Eq + Hash
+{
+    type Item = T;
+    type IntoIter = SetIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SetIntoIter {
+            iter: self.table.into_iter()
+        }
+    }
+}
+
+impl<T> Iterator for SetIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
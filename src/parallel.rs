@@ -0,0 +1,191 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::types::Effector;
+use crate::fsm::{FSM, FSMError, Cursor, EMPTY_STRING};
+
+/// Runner of several FSMs (orthogonal regions) in lockstep over one input,
+/// so input is decoded only once.
+/// For every item regions proceed in order of addition.
+/// Regions share types of states and effects 
+/// (e.g. single enum covers states of all regions)
+pub struct Parallel<'f, State, Effect, Error = (), Input = char>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    regions: Vec<&'f FSM<State, Effect, Error, (), Input>>
+}
+
+/// Error of some region of parallel run
+#[derive(Copy, Clone, Debug)]
+pub struct ParallelError<'a, State, Error = (), Input = char>
+    where State: Eq + PartialEq + Copy + Hash + Debug
+{
+    /// Position of region that failed (in order of addition)
+    pub region: usize,
+    /// Error of region
+    pub error: FSMError<'a, State, Error, Input>
+}
+
+/// Alias for effector of single region
+type RegionEffector<'a, Effect, State, Error, Input> =
+    Option<&'a mut dyn Effector<Effect, State, Error, Input>>;
+
+impl<'f, State, Effect, Error, Input> Parallel<'f, State, Effect, Error, Input>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone,
+          Input: Copy + PartialEq
+{
+    /// Creates runner without regions
+    pub fn new() -> Self {
+        Self {
+            regions: Vec::new()
+        }
+    }
+
+    /// Appends FSM as the last region
+    /// - fsm: region
+    pub fn with(mut self, fsm: &'f FSM<State, Effect, Error, (), Input>) -> Self {
+        self.regions.push(fsm);
+        self
+    }
+
+    /// Returns number of regions
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Checks whether there are no regions
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Runs items of some input through every region (see "FSM::proceed_iter")
+    /// - inputs: runnable items,
+    /// - effectors: effector of every region (in order of regions, missing ones are None)
+    pub fn proceed_iter<'a, I>(
+        &self,
+        inputs: I,
+        effectors: Vec<RegionEffector<'a, Effect, State, Error, Input>>
+    ) -> Result<(), ParallelError<'a, State, Error, Input>>
+        where I: IntoIterator<Item = Input>
+    {
+        self.run(&EMPTY_STRING, inputs.into_iter(), effectors, false)
+    }
+
+    /// Runs items of some input through every region
+    /// dispatching effects of all regions to one effector
+    /// - inputs: runnable items,
+    /// - effector: shared effector
+    pub fn proceed_iter_shared<'a, I>(
+        &self,
+        inputs: I,
+        effector: RegionEffector<'a, Effect, State, Error, Input>
+    ) -> Result<(), ParallelError<'a, State, Error, Input>>
+        where I: IntoIterator<Item = Input>
+    {
+        self.run(&EMPTY_STRING, inputs.into_iter(), vec![effector], true)
+    }
+
+    /// Proceeds every item in every region that isn't finished yet.
+    /// Region finishes on its own if its effector stops it;
+    /// the first error of any region aborts the whole run
+    fn run<'a, I>(
+        &self,
+        string: &'a String,
+        inputs: I,
+        mut effectors: Vec<RegionEffector<'a, Effect, State, Error, Input>>,
+        is_shared: bool
+    ) -> Result<(), ParallelError<'a, State, Error, Input>>
+        where I: Iterator<Item = Input>
+    {
+        effectors.resize_with(if is_shared { 1 } else { self.regions.len() }, || None);
+
+        let effector_id = |region: usize| if is_shared { 0 } else { region };
+        let fail = |region: usize| move |error| ParallelError { region, error };
+
+        let mut cursors: Vec<Cursor<State>> = Vec::with_capacity(self.regions.len());
+
+        for (region, fsm) in self.regions.iter().enumerate() {
+            let cursor = fsm.start(string, &mut effectors[effector_id(region)], &mut None, &mut None)
+                .map_err(fail(region))?;
+
+            cursors.push(cursor);
+        }
+
+        for (char_id, ch) in inputs.enumerate() {
+            if cursors.iter().all(|cursor| cursor.is_finished) {
+                return Ok(());
+            }
+
+            for (region, fsm) in self.regions.iter().enumerate() {
+                if cursors[region].is_finished {
+                    continue;
+                }
+
+                fsm.step(
+                    &mut cursors[region],
+                    string,
+                    char_id,
+                    ch,
+                    &mut effectors[effector_id(region)],
+                    |_| None,
+                    &mut None,
+                    &mut None
+                ).map_err(fail(region))?;
+            }
+        }
+
+        for (region, fsm) in self.regions.iter().enumerate() {
+            if cursors[region].is_finished {
+                continue;
+            }
+
+            fsm.end(&mut cursors[region], string, &mut effectors[effector_id(region)], &mut None, &mut None)
+                .map_err(fail(region))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs through strings (for regions over characters)
+impl<'f, State, Effect, Error> Parallel<'f, State, Effect, Error>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    /// Runs some string through every region (see "FSM::proceed")
+    /// - string: runnable string,
+    /// - effectors: effector of every region (in order of regions, missing ones are None)
+    pub fn proceed<'a>(
+        &self,
+        string: &'a String,
+        effectors: Vec<RegionEffector<'a, Effect, State, Error, char>>
+    ) -> Result<(), ParallelError<'a, State, Error>> {
+        self.run(string, string.chars(), effectors, false)
+    }
+
+    /// Runs some string through every region
+    /// dispatching effects of all regions to one effector
+    /// - string: runnable string,
+    /// - effector: shared effector
+    pub fn proceed_shared<'a>(
+        &self,
+        string: &'a String,
+        effector: RegionEffector<'a, Effect, State, Error, char>
+    ) -> Result<(), ParallelError<'a, State, Error>> {
+        self.run(string, string.chars(), vec![effector], true)
+    }
+}
+
+impl<'f, State, Effect, Error, Input> Default for Parallel<'f, State, Effect, Error, Input>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone,
+          Input: Copy + PartialEq
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,264 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::map::Map;
+use crate::types::{Predicate, Transition, Effector, Control, StreamData};
+use crate::fsm::{FSMError, dispatch_all};
+
+/// Operation on stack of pushdown automata
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StackOp<Stack> {
+    /// Stack isn't changed
+    Keep,
+    /// Symbol is pushed onto stack
+    Push(Stack),
+    /// Top symbol is removed from stack
+    Pop,
+    /// Top symbol is replaced by another one
+    Replace(Stack)
+}
+
+/// Transition of pushdown automata to next state:
+/// transition of FSM which is also validated by top of stack.
+/// Guarded transitions never match (runs of PDA have no context)
+pub struct PDATransition<State, Stack, Effect>
+    where State: Eq + PartialEq + Copy,
+          Stack: Clone + PartialEq,
+          Effect: Clone
+{
+    /// Transition that validates current character (and registers)
+    /// and holds next state and side effects
+    pub transition: Transition<State, Effect>,
+    /// Symbol that must be on top of stack.
+    /// If None then transition doesn't inspect stack (it may be empty)
+    pub top: Option<Stack>,
+    /// Operation on stack after successful validation of transition
+    pub op: StackOp<Stack>
+}
+
+/// Pushdown automata with side effects: FSM with stack of symbols,
+/// so it can validate nested structures (e.g. balanced brackets).
+/// String is accepted only if stack is empty after last character
+pub struct PDA<State, Stack, Effect, Error = ()>
+    where State: Eq + PartialEq + Copy + Hash,
+          Stack: Clone + PartialEq,
+          Effect: Clone
+{
+    /// State at beginning of running through stream
+    initial_state: State,
+    /// Transition graph that connects every state of PDA
+    /// to some next states by transitions
+    transition_table: Map<State, Vec<PDATransition<State, Stack, Effect>>>,
+    /// Side effects that occur (in order) after proceeding
+    /// last character of string
+    post_effects: Vec<Effect>,
+    error: PhantomData<fn() -> Error>
+}
+
+impl<State, Stack, Effect> PDATransition<State, Stack, Effect>
+    where State: Eq + PartialEq + Copy,
+          Stack: Clone + PartialEq,
+          Effect: Clone
+{
+    /// Creates new transition
+    /// - to: next state,
+    /// - condition: predicate for character,
+    /// - top: required symbol on top of stack,
+    /// - op: operation on stack,
+    /// - effects: side effects (dispatched in order)
+    pub fn new(
+        to: State,
+        condition: Option<Predicate>,
+        top: Option<Stack>,
+        op: StackOp<Stack>,
+        effects: Vec<Effect>
+    ) -> Self {
+        Self::extending(Transition::with_effects(to, condition, effects), top, op)
+    }
+
+    /// Creates new transition from transition of FSM
+    /// (e.g. literal one or one with registers)
+    /// - transition: transition for character,
+    /// - top: required symbol on top of stack,
+    /// - op: operation on stack
+    pub fn extending(transition: Transition<State, Effect>, top: Option<Stack>, op: StackOp<Stack>) -> Self {
+        Self {
+            transition,
+            top,
+            op
+        }
+    }
+
+    /// Matches next state and side effects for current character, registers and stack
+    /// - ch: current character (of stream),
+    /// - registers: values of registers of run,
+    /// - stack: current stack (top is the last symbol)
+    pub fn transit(&self, ch: char, registers: &[u64], stack: &[Stack]) -> (Option<State>, &[Effect]) {
+        let is_on_top = match self.top.as_ref() {
+            Some(top) => stack.last() == Some(top),
+            None => true
+        };
+
+        match self.transition.transit(ch) {
+            (Some(new_state), effects) if is_on_top && self.transition.accepts_registers(registers) => {
+                (Some(new_state), effects)
+            },
+            _ => (None, &[])
+        }
+    }
+}
+
+impl<State, Stack, Effect, Error> PDA<State, Stack, Effect, Error>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Stack: Clone + PartialEq,
+          Effect: Clone
+{
+    /// Creates new instance of PDA (like "FSM::new", targets of transitions
+    /// aren't checked: run fails with "FSMError::StateDoesNotExist" in unknown state)
+    /// - initial_state: starting state,
+    /// - transition_table: transition graph,
+    /// - post_effects: side effects after last character
+    pub fn new<'a>(
+        initial_state: State,
+        transition_table: Map<State, Vec<PDATransition<State, Stack, Effect>>>,
+        post_effects: Vec<Effect>
+    ) -> Result<Self, FSMError<'a, State, Error>> {
+        if !transition_table.contains_key(&initial_state) {
+            return Err(FSMError::StateDoesNotExist(initial_state));
+        }
+
+        Ok(Self {
+            initial_state,
+            transition_table,
+            post_effects,
+            error: PhantomData
+        })
+    }
+
+    /// Returns starting state
+    pub fn initial_state(&self) -> State {
+        self.initial_state
+    }
+
+    /// Returns ordered transitions of some state
+    /// (or None if state doesn't belong to PDA)
+    /// - state: source state
+    pub fn transitions(&self, state: State) -> Option<&Vec<PDATransition<State, Stack, Effect>>> {
+        self.transition_table.get(&state)
+    }
+
+    /// Returns post-effects in order of dispatch
+    pub fn post_effects(&self) -> &Vec<Effect> {
+        &self.post_effects
+    }
+
+    /// Runs some string through PDA to validate it (and apply some effects).
+    /// For every character the first transition that matches character,
+    /// registers (like in "FSM::proceed") and top of stack is taken,
+    /// then its register updates and stack operation are applied
+    /// and its effects are dispatched.
+    /// Popping empty stack fails with "FSMError::StackUnderflow",
+    /// non-empty stack after last character fails with "FSMError::NonEmptyStack"
+    /// (post-effects are dispatched only if stack is empty).
+    /// Effector controls run by returned value (see "Control")
+    /// - string: runnable string,
+    /// - effector: module that mutates some data by effects
+    pub fn proceed<'a>(
+        &self,
+        string: &'a String,
+        mut effector: Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<(), FSMError<'a, State, Error>> {
+        let mut curr_state = self.initial_state;
+        let mut stack: Vec<Stack> = Vec::new();
+        let mut registers: Vec<u64> = Vec::new();
+
+        for (char_id, ch) in string.chars().enumerate() {
+            let input_data = StreamData {
+                string,
+                index: char_id,
+                character: Some(ch)
+            };
+
+            let transitions = match self.transition_table.get(&curr_state) {
+                Some(transitions) => transitions,
+                None => return Err(FSMError::StateDoesNotExist(curr_state))
+            };
+
+            let matched = transitions.iter()
+                .find_map(|transition| match transition.transit(ch, &registers, &stack) {
+                    (Some(new_state), effects) => Some((transition, new_state, effects)),
+                    _ => None
+                });
+
+            let (transition, new_state, effects) = match matched {
+                Some(matched) => matched,
+                None => return Err(FSMError::NoValidTransition {
+                    from: curr_state,
+                    input_data
+                })
+            };
+
+            for update in transition.transition.updates.iter() {
+                update.apply(&mut registers);
+            }
+
+            match &transition.op {
+                StackOp::Keep => {},
+                StackOp::Push(symbol) => stack.push(symbol.clone()),
+                StackOp::Pop => if stack.pop().is_none() {
+                    return Err(FSMError::StackUnderflow {
+                        from: curr_state,
+                        input_data
+                    });
+                },
+                StackOp::Replace(symbol) => match stack.last_mut() {
+                    Some(top) => *top = symbol.clone(),
+                    None => return Err(FSMError::StackUnderflow {
+                        from: curr_state,
+                        input_data
+                    })
+                }
+            }
+
+            curr_state = new_state;
+
+            match dispatch_all(&mut effector, effects.iter(), input_data, None) {
+                Control::Continue => {},
+                Control::Stop => return Ok(()),
+                Control::Error(error) => return Err(FSMError::Effect(error)),
+                Control::GoTo(state) => curr_state = self.target(state)?
+            }
+        }
+
+        if !stack.is_empty() {
+            return Err(FSMError::NonEmptyStack {
+                state: curr_state,
+                depth: stack.len()
+            });
+        }
+
+        let input_data = StreamData {
+            string,
+            index: string.len(),
+            character: None
+        };
+
+        match dispatch_all(&mut effector, self.post_effects.iter(), input_data, None) {
+            Control::Error(error) => Err(FSMError::Effect(error)),
+            Control::GoTo(state) => self.target(state).map(|_| ()),
+            _ => Ok(())
+        }
+    }
+
+    /// Checks state that effector goes to
+    /// - state: next state
+    fn target<'a>(&self, state: State) -> Result<State, FSMError<'a, State, Error>> {
+        if self.transition_table.contains_key(&state) {
+            Ok(state)
+        } else {
+            Err(FSMError::StateDoesNotExist(state))
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use crate::map::Map;
+use crate::types::Transition;
+
+/// Test of integer register (register index, bound)
+/// that must hold for transition to succeed.
+/// Registers belong to single run and start from zero
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RegisterTest {
+    Less(usize, u64),
+    LessOrEqual(usize, u64),
+    Equal(usize, u64),
+    GreaterOrEqual(usize, u64),
+    Greater(usize, u64)
+}
+
+/// Update of integer register (by register index)
+/// that is applied when transition succeeds
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RegisterUpdate {
+    /// Adds one to register (saturating at maximum)
+    Increment(usize),
+    /// Sets register to zero
+    Reset(usize)
+}
+
+/// Inconsistency of register bounds found by "FSM::analyze_registers"
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RegisterIssue<State>
+    where State: Eq + PartialEq + Copy + Hash + Debug
+{
+    /// Tests of transition (by index) contradict each other for some register,
+    /// so transition never succeeds
+    UnsatisfiableTests {
+        from: State,
+        index: usize,
+        register: usize
+    },
+    /// Register is tested against non-zero lower bound but never incremented
+    /// (so tests that need it to be non-zero never hold)
+    NeverIncremented(usize),
+    /// Register is updated but never tested
+    NeverTested(usize)
+}
+
+impl RegisterTest {
+    /// Returns index of tested register
+    pub fn register(&self) -> usize {
+        match *self {
+            Self::Less(register, _) => register,
+            Self::LessOrEqual(register, _) => register,
+            Self::Equal(register, _) => register,
+            Self::GreaterOrEqual(register, _) => register,
+            Self::Greater(register, _) => register
+        }
+    }
+
+    /// Checks value of register
+    /// - value: current value of tested register
+    pub fn holds(&self, value: u64) -> bool {
+        let (min, max) = self.bounds();
+
+        min.is_some_and(|min| value >= min) && max.is_none_or(|max| value <= max)
+    }
+
+    /// Returns inclusive range of values that pass test
+    /// (lower bound is None if no value passes, upper bound is None if unbounded)
+    fn bounds(&self) -> (Option<u64>, Option<u64>) {
+        match *self {
+            Self::Less(_, bound) => match bound {
+                0 => (None, None),
+                bound => (Some(0), Some(bound - 1))
+            },
+            Self::LessOrEqual(_, bound) => (Some(0), Some(bound)),
+            Self::Equal(_, bound) => (Some(bound), Some(bound)),
+            Self::GreaterOrEqual(_, bound) => (Some(bound), None),
+            Self::Greater(_, bound) => (bound.checked_add(1), None)
+        }
+    }
+}
+
+impl RegisterUpdate {
+    /// Returns index of updated register
+    pub fn register(&self) -> usize {
+        match *self {
+            Self::Increment(register) => register,
+            Self::Reset(register) => register
+        }
+    }
+
+    /// Applies update to registers of run (growing them if needed)
+    /// - registers: values of registers
+    pub fn apply(&self, registers: &mut Vec<u64>) {
+        let register = self.register();
+
+        if registers.len() <= register {
+            registers.resize(register + 1, 0);
+        }
+
+        match *self {
+            Self::Increment(_) => registers[register] = registers[register].saturating_add(1),
+            Self::Reset(_) => registers[register] = 0
+        }
+    }
+}
+
+/// Returns value of register (registers that were never updated are zero)
+/// - registers: values of registers,
+/// - register: index of register
+pub(crate) fn register_value(registers: &[u64], register: usize) -> u64 {
+    registers.get(register).copied().unwrap_or(0)
+}
+
+/// Collects register issues of FSM (see "FSM::analyze_registers")
+/// - transition_table: transition graph,
+/// - end_tests: tests of registers at end of input
+pub(crate) fn analyze<State, Effect, Context, Input>(
+    transition_table: &Map<State, Vec<Transition<State, Effect, Context, Input>>>,
+    end_tests: &Map<State, Vec<RegisterTest>>
+) -> Vec<RegisterIssue<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug + Ord,
+          Effect: Clone
+{
+    let mut issues = Vec::new();
+    let mut incremented = BTreeSet::new();
+    let mut tested = BTreeSet::new();
+    let mut updated = BTreeSet::new();
+
+    for transition in transition_table.values().flatten() {
+        for test in transition.tests.iter() {
+            tested.insert(test.register());
+        }
+
+        for update in transition.updates.iter() {
+            updated.insert(update.register());
+
+            if let RegisterUpdate::Increment(register) = update {
+                incremented.insert(*register);
+            }
+        }
+    }
+
+    let mut never_incremented = BTreeSet::new();
+
+    for test in end_tests.values().flatten() {
+        tested.insert(test.register());
+
+        if !test.holds(0) && !incremented.contains(&test.register()) {
+            never_incremented.insert(test.register());
+        }
+    }
+
+    // states of map come in arbitrary order
+    let mut sources: Vec<_> = transition_table.iter().collect();
+    sources.sort_unstable_by_key(|(from, _)| **from);
+
+    for (from, transitions) in sources {
+        for (index, transition) in transitions.iter().enumerate() {
+            let mut unsatisfiable = BTreeSet::new();
+
+            for test in transition.tests.iter() {
+                let register = test.register();
+                let (min, max) = transition.tests.iter()
+                    .filter(|other| other.register() == register)
+                    .fold((Some(0), None), |(min, max), other| {
+                        let (other_min, other_max) = other.bounds();
+
+                        (
+                            min.and_then(|min: u64| other_min.map(|other_min| min.max(other_min))),
+                            match (max, other_max) {
+                                (Some(max), Some(other_max)) => Some(u64::min(max, other_max)),
+                                (max, other_max) => max.or(other_max)
+                            }
+                        )
+                    });
+
+                if min.is_none() || matches!((min, max), (Some(min), Some(max)) if min > max) {
+                    unsatisfiable.insert(register);
+                }
+
+                if !test.holds(0) && !incremented.contains(&register) {
+                    never_incremented.insert(register);
+                }
+            }
+
+            issues.extend(unsatisfiable.into_iter().map(
+                |register| RegisterIssue::UnsatisfiableTests { from: *from, index, register }
+            ));
+        }
+    }
+
+    issues.extend(never_incremented.into_iter().map(RegisterIssue::NeverIncremented));
+
+    let never_tested: BTreeSet<usize> = updated.difference(&tested).copied().collect();
+    issues.extend(never_tested.into_iter().map(RegisterIssue::NeverTested));
+
+    issues
+}
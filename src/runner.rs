@@ -0,0 +1,190 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use crate::types::Effector;
+use crate::fsm::{FSM, FSMError, Cursor, EMPTY_STRING};
+
+/// Single run through FSM that is fed by items one by one
+/// (e.g. as they come from socket or channel).
+/// Everything that changes during run (current state, registers, history)
+/// belongs to runner and FSM is only borrowed,
+/// so one FSM (e.g. in "Arc" or "static") may serve runners of many threads at once.
+/// Input data of effects holds empty string (like in "FSM::proceed_iter");
+/// guarded transitions match only items that are fed with context (see "feed_in_context")
+pub struct Runner<'f, State, Effect, Error = (), Context = (), Input = char>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    fsm: &'f FSM<State, Effect, Error, Context, Input>,
+    /// Progress of run
+    cursor: Cursor<State>,
+    /// Run failed, so it can't go on
+    is_failed: bool
+}
+
+impl<'f, State, Effect, Error, Context, Input> Runner<'f, State, Effect, Error, Context, Input>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone,
+          Input: Copy + PartialEq
+{
+    /// Starts run (dispatches entry effects of initial state)
+    /// - fsm: runnable FSM,
+    /// - effector: module that mutates some data by effects
+    pub fn start(
+        fsm: &'f FSM<State, Effect, Error, Context, Input>,
+        mut effector: Option<&mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<Self, FSMError<'static, State, Error, Input>> {
+        let cursor = fsm.start(&EMPTY_STRING, &mut effector, &mut None, &mut None)?;
+
+        Ok(Self {
+            fsm,
+            cursor,
+            is_failed: false
+        })
+    }
+
+    /// Returns current state
+    pub fn state(&self) -> State {
+        self.cursor.state
+    }
+
+    /// Returns number of proceeded items
+    pub fn len(&self) -> usize {
+        self.cursor.length
+    }
+
+    /// Checks whether no item is proceeded yet
+    pub fn is_empty(&self) -> bool {
+        self.cursor.length == 0
+    }
+
+    /// Checks whether run is over (it's stopped by effector,
+    /// failed or finished), so next items aren't proceeded
+    pub fn is_finished(&self) -> bool {
+        self.cursor.is_finished
+    }
+
+    /// Checks whether run failed (then feeding and finishing fail with "RunFailed")
+    pub fn is_failed(&self) -> bool {
+        self.is_failed
+    }
+
+    /// Proceeds next item like "FSM::proceed_iter" does
+    /// (item is ignored if run is stopped by effector).
+    /// Run is over after error
+    /// - item: next item of input,
+    /// - effector: module that mutates some data by effects
+    pub fn feed(
+        &mut self,
+        item: Input,
+        effector: Option<&mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<(), FSMError<'static, State, Error, Input>> {
+        self.feed_with(item, effector, |_| None)
+    }
+
+    /// Proceeds next item like "feed" does with context
+    /// that is both effector and data read by guards (see "FSM::proceed_in_context")
+    /// - item: next item of input,
+    /// - context: module that mutates itself by effects
+    pub fn feed_in_context(
+        &mut self,
+        item: Input,
+        context: &mut Context
+    ) -> Result<(), FSMError<'static, State, Error, Input>>
+        where Context: Effector<Effect, State, Error, Input>
+    {
+        self.feed_with(item, Some(context), |context| Some(context))
+    }
+
+    /// Finishes run after last item (dispatches exit effects of current state
+    /// and post-effects unless run is stopped by effector) and returns last state
+    /// - effector: module that mutates some data by effects
+    pub fn finish(
+        mut self,
+        effector: Option<&mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<State, FSMError<'static, State, Error, Input>> {
+        self.end(effector)?;
+
+        Ok(self.cursor.state)
+    }
+
+    /// Ends run after last item like "finish" does, but keeps runner
+    /// - effector: module that mutates some data by effects
+    pub(crate) fn end(
+        &mut self,
+        mut effector: Option<&mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<(), FSMError<'static, State, Error, Input>> {
+        if self.is_failed {
+            return Err(FSMError::RunFailed);
+        }
+
+        if !self.cursor.is_finished {
+            self.fsm.end(&mut self.cursor, &EMPTY_STRING, &mut effector, &mut None, &mut None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Proceeds next item with effector that may also provide context for guards
+    /// - item: next item of input,
+    /// - effector: module that mutates some data by effects,
+    /// - context: provider of context
+    pub(crate) fn feed_with<E>(
+        &mut self,
+        item: Input,
+        mut effector: Option<&mut E>,
+        context: fn(&E) -> Option<&Context>
+    ) -> Result<(), FSMError<'static, State, Error, Input>>
+        where E: Effector<Effect, State, Error, Input> + ?Sized
+    {
+        if self.is_failed {
+            return Err(FSMError::RunFailed);
+        }
+
+        if self.cursor.is_finished {
+            return Ok(());
+        }
+
+        let index = self.cursor.length;
+        let result = self.fsm.step(
+            &mut self.cursor,
+            &EMPTY_STRING,
+            index,
+            item,
+            &mut effector,
+            context,
+            &mut None,
+            &mut None
+        );
+
+        if result.is_err() {
+            self.cursor.is_finished = true;
+            self.is_failed = true;
+        }
+
+        result
+    }
+
+    /// Goes on from given state (see "Control::GoTo").
+    /// Run is over if state doesn't belong to FSM
+    /// - state: next state
+    #[cfg(feature = "async")]
+    pub(crate) fn go_to(&mut self, state: State) -> Result<(), FSMError<'static, State, Error, Input>> {
+        match self.fsm.target(state, &self.cursor.remembered) {
+            Ok(state) => {
+                self.cursor.state = state;
+                Ok(())
+            },
+            Err(error) => {
+                self.cursor.is_finished = true;
+                self.is_failed = true;
+                Err(error)
+            }
+        }
+    }
+
+    /// Finishes run before the end of input (see "Control::Stop")
+    #[cfg(feature = "async")]
+    pub(crate) fn stop(&mut self) {
+        self.cursor.is_finished = true;
+    }
+}
@@ -0,0 +1,696 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::format;
+use crate::map::Map;
+use crate::fsm::FSM;
+use crate::types::Transition;
+use crate::vocabulary::Vocabulary;
+
+/// SCXML namespace written into root element of exported documents
+const SCXML_NAMESPACE: &str = "http://www.w3.org/2005/07/scxml";
+
+/// Id of datamodel entry that keeps post-effect of FSM
+const POST_EFFECT_ID: &str = "post_effect";
+
+/// Maximal depth of nested elements of imported document
+/// (elements are parsed recursively, so deeper documents are rejected
+/// before they overflow stack)
+const MAX_DEPTH: usize = 128;
+
+/// Names of states, conditions and effects used in SCXML documents:
+/// states are ids of SCXML states, conditions are "cond" of transitions
+/// and effects are events of <send> actions
+pub type ScxmlVocabulary<State, Effect> = Vocabulary<State, Effect>;
+
+/// Error that occurs during export or import of SCXML document
+#[derive(Clone, Debug)]
+pub enum ScxmlError<State>
+    where State: Eq + PartialEq + Copy + Hash + Debug
+{
+    /// Malformed XML at some byte position of document
+    Syntax {
+        position: usize,
+        reason: &'static str
+    },
+    /// Element that isn't expected at its place
+    UnexpectedElement(String),
+    /// Element that lacks required attribute
+    MissingAttribute {
+        element: String,
+        attribute: &'static str
+    },
+    /// SCXML feature beyond flat (non-hierarchical) subset
+    Unsupported(String),
+    /// State is declared twice
+    DuplicateState(String),
+    /// Name of state isn't registered or declared
+    UnknownState(String),
+    /// Name of condition isn't registered
+    UnknownCondition(String),
+    /// Name of effect isn't registered
+    UnknownEffect(String),
+    /// State of FSM isn't registered in vocabulary
+    UnnamedState(State),
+    /// Name can't be written into document
+    /// (names of post-effects can't have "," and "'")
+    InvalidName(String),
+    /// Condition of transition has no name or its name isn't registered in vocabulary
+    UnnamedCondition {
+        from: State,
+        index: usize
+    },
+    /// Effect isn't registered in vocabulary
+    /// (index is None for post-effect)
+    UnnamedEffect {
+        from: State,
+        index: Option<usize>
+    },
+    /// Entry or exit effect of state isn't registered in vocabulary
+    UnnamedStateEffect(State),
+    /// Transition has guard, literal symbol or register operations 
+    /// (that have no SCXML counterpart)
+    GuardedTransition {
+        from: State,
+        index: usize
+    }
+}
+
+/// Exports FSM into SCXML document.
+/// Every state becomes <state> (in order of registration in vocabulary),
+/// every transition becomes <transition> with "cond" (name of condition, if conditional)
+/// and effects as <send> actions, entry and exit effects of states
+/// become <send> actions of <onentry> and <onexit>, post-effects are kept
+/// in <datamodel> as list of names in data with id "post_effect".
+/// Guarded transitions and end tests of registers can't be exported
+/// - fsm: exported FSM,
+/// - vocabulary: names of states, conditions and effects
+pub fn to_scxml<State, Effect, Error, Context>(
+    fsm: &FSM<State, Effect, Error, Context>,
+    vocabulary: &ScxmlVocabulary<State, Effect>
+) -> Result<String, ScxmlError<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    if fsm.is_hierarchical() {
+        return Err(ScxmlError::Unsupported(String::from("nested states")));
+    }
+
+    for state in fsm.states() {
+        if vocabulary.state_name(state).is_none() {
+            return Err(ScxmlError::UnnamedState(state));
+        }
+
+        if fsm.end_tests(state).is_some() {
+            return Err(ScxmlError::Unsupported(String::from("end tests of registers")));
+        }
+    }
+
+    let initial_state = fsm.initial_state();
+    let mut document = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    document.push_str(&format!(
+        "<scxml xmlns=\"{}\" version=\"1.0\" initial=\"{}\">\n",
+        SCXML_NAMESPACE,
+        escape(named_state(vocabulary, initial_state)?)
+    ));
+
+    if !fsm.post_effects().is_empty() {
+        let mut names = Vec::new();
+
+        for effect in fsm.post_effects().iter() {
+            let name = vocabulary.effect_name(effect).ok_or(
+                ScxmlError::UnnamedEffect { from: initial_state, index: None }
+            )?;
+
+            // names are quoted and separated by commas in expression
+            if name.contains([',', '\'']) {
+                return Err(ScxmlError::InvalidName(String::from(name)));
+            }
+
+            names.push(format!("'{}'", escape(name)));
+        }
+
+        document.push_str("    <datamodel>\n");
+        document.push_str(&format!(
+            "        <data id=\"{}\" expr=\"[{}]\"/>\n",
+            POST_EFFECT_ID,
+            names.join(", ")
+        ));
+        document.push_str("    </datamodel>\n");
+    }
+
+    for (state, name) in vocabulary.states() {
+        let transitions = match fsm.transitions(state) {
+            Some(transitions) => transitions,
+            None => continue
+        };
+
+        let entry_effect = fsm.entry_effect(state);
+        let exit_effect = fsm.exit_effect(state);
+
+        if transitions.is_empty() && entry_effect.is_none() && exit_effect.is_none() {
+            document.push_str(&format!("    <state id=\"{}\"/>\n", escape(name)));
+            continue;
+        }
+
+        document.push_str(&format!("    <state id=\"{}\">\n", escape(name)));
+
+        for (element, effect) in [("onentry", entry_effect), ("onexit", exit_effect)].iter() {
+            if let Some(effect) = effect {
+                let effect = vocabulary.effect_name(effect).ok_or(
+                    ScxmlError::UnnamedStateEffect(state)
+                )?;
+
+                document.push_str(&format!("        <{}>\n", element));
+                document.push_str(&format!(
+                    "            <send event=\"{}\"/>\n",
+                    escape(effect)
+                ));
+                document.push_str(&format!("        </{}>\n", element));
+            }
+        }
+
+        for (index, transition) in transitions.iter().enumerate() {
+            if transition.guard.is_some() || transition.symbol.is_some() || !transition.tests.is_empty() || !transition.updates.is_empty() {
+                return Err(ScxmlError::GuardedTransition { from: state, index });
+            }
+
+            document.push_str("        <transition");
+
+            if transition.condition.is_some() {
+                let condition = transition.condition_name
+                    .filter(|name| vocabulary.condition_by_name(name).is_some())
+                    .ok_or(ScxmlError::UnnamedCondition { from: state, index })?;
+
+                document.push_str(&format!(" cond=\"{}\"", escape(condition)));
+            }
+
+            document.push_str(&format!(
+                " target=\"{}\"",
+                escape(named_state(vocabulary, transition.to)?)
+            ));
+
+            if transition.effects.is_empty() {
+                document.push_str("/>\n");
+                continue;
+            }
+
+            document.push_str(">\n");
+
+            for effect in transition.effects.iter() {
+                let effect = vocabulary.effect_name(effect).ok_or(
+                    ScxmlError::UnnamedEffect { from: state, index: Some(index) }
+                )?;
+
+                document.push_str(&format!(
+                    "            <send event=\"{}\"/>\n",
+                    escape(effect)
+                ));
+            }
+
+            document.push_str("        </transition>\n");
+        }
+
+        document.push_str("    </state>\n");
+    }
+
+    document.push_str("</scxml>\n");
+
+    Ok(document)
+}
+
+/// Imports FSM from SCXML document.
+/// Only flat subset is supported: <state> and <final> elements
+/// directly inside <scxml>, each with <transition> elements
+/// that have single target, optional "cond" (but no "event", as runs have no events)
+/// and <send> (or <raise>) actions which are resolved to effects.
+/// States may have <onentry> and <onexit> with at most one action each.
+/// If "initial" attribute is absent, first state becomes initial.
+/// Documents with elements nested deeper than 128 levels are rejected as malformed
+/// - source: SCXML document,
+/// - vocabulary: names of states, conditions and effects
+pub fn from_scxml<State, Effect, Error>(
+    source: &str,
+    vocabulary: &ScxmlVocabulary<State, Effect>
+) -> Result<FSM<State, Effect, Error>, ScxmlError<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    let root = XmlParser::new(source).parse_document()?;
+
+    if root.local_name() != "scxml" {
+        return Err(ScxmlError::UnexpectedElement(root.name.clone()));
+    }
+
+    let mut transition_table: Map<State, Vec<Transition<State, Effect>>> = Map::new();
+    let mut first_state: Option<String> = None;
+    let mut post_effects: Vec<Effect> = Vec::new();
+    let mut targets: Vec<String> = Vec::new();
+    let mut state_effects: Vec<(State, Option<Effect>, Option<Effect>)> = Vec::new();
+
+    for child in root.children.iter() {
+        match child.local_name() {
+            "state" | "final" => {
+                let name = child.required_attribute("id")?;
+                let state = vocabulary.state_by_name(name).ok_or_else(
+                    || ScxmlError::UnknownState(String::from(name))
+                )?;
+
+                if transition_table.contains_key(&state) {
+                    return Err(ScxmlError::DuplicateState(String::from(name)));
+                }
+
+                if first_state.is_none() {
+                    first_state = Some(String::from(name));
+                }
+
+                let mut transitions = Vec::new();
+                let mut entry_effect = None;
+                let mut exit_effect = None;
+
+                for element in child.children.iter() {
+                    match element.local_name() {
+                        "transition" => {
+                            let (transition, target) = import_transition(element, vocabulary)?;
+                            transitions.push(transition);
+                            targets.push(target);
+                        },
+                        "state" | "parallel" | "history" | "initial" => return Err(
+                            ScxmlError::Unsupported(format!("nested <{}>", element.name))
+                        ),
+                        "onentry" => entry_effect = import_state_action(element, vocabulary)?,
+                        "onexit" => exit_effect = import_state_action(element, vocabulary)?,
+                        "invoke" => return Err(
+                            ScxmlError::Unsupported(format!("<{}>", element.name))
+                        ),
+                        _ => return Err(ScxmlError::UnexpectedElement(element.name.clone()))
+                    }
+                }
+
+                transition_table.insert(state, transitions);
+                state_effects.push((state, entry_effect, exit_effect));
+            },
+            "datamodel" => {
+                for data in child.children.iter() {
+                    if data.local_name() != "data" {
+                        return Err(ScxmlError::UnexpectedElement(data.name.clone()));
+                    }
+
+                    if data.required_attribute("id")? != POST_EFFECT_ID {
+                        continue;
+                    }
+
+                    let expr = data.required_attribute("expr")?.trim();
+                    let list = match expr.strip_prefix('[').and_then(|expr| expr.strip_suffix(']')) {
+                        Some(list) => list,
+                        None => expr
+                    };
+
+                    for name in list.split(',').map(|name| name.trim().trim_matches('\'')) {
+                        if name.is_empty() {
+                            continue;
+                        }
+
+                        post_effects.push(vocabulary.effect_by_name(name).ok_or_else(
+                            || ScxmlError::UnknownEffect(String::from(name))
+                        )?);
+                    }
+                }
+            },
+            "parallel" | "history" => return Err(
+                ScxmlError::Unsupported(format!("<{}>", child.name))
+            ),
+            _ => return Err(ScxmlError::UnexpectedElement(child.name.clone()))
+        }
+    }
+
+    for target in targets.iter() {
+        let declared = vocabulary.state_by_name(target)
+            .map(|state| transition_table.contains_key(&state))
+            .unwrap_or(false);
+
+        if !declared {
+            return Err(ScxmlError::UnknownState(target.clone()));
+        }
+    }
+
+    let initial_name = match root.attribute("initial") {
+        Some(name) => String::from(name),
+        None => first_state.ok_or_else(
+            || ScxmlError::UnexpectedElement(root.name.clone())
+        )?
+    };
+
+    let initial_state = vocabulary.state_by_name(&initial_name).ok_or_else(
+        || ScxmlError::UnknownState(initial_name.clone())
+    )?;
+
+    let mut fsm = FSM::new(initial_state, transition_table, None)
+        .map_err(|_| ScxmlError::UnknownState(initial_name))?;
+
+    for effect in post_effects {
+        fsm.push_post_effect(effect);
+    }
+
+    for (state, entry_effect, exit_effect) in state_effects {
+        fsm.set_entry_effect(state, entry_effect)
+            .and_then(|_| fsm.set_exit_effect(state, exit_effect))
+            .map_err(|_| ScxmlError::UnnamedState(state))?;
+    }
+
+    Ok(fsm)
+}
+
+fn import_transition<State, Effect>(
+    element: &XmlElement,
+    vocabulary: &ScxmlVocabulary<State, Effect>
+) -> Result<(Transition<State, Effect>, String), ScxmlError<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    if element.attribute("event").is_some() {
+        return Err(ScxmlError::Unsupported(String::from("event of <transition>")));
+    }
+
+    let target = element.required_attribute("target")?.trim();
+
+    if target.contains(char::is_whitespace) {
+        return Err(ScxmlError::Unsupported(String::from("multiple targets of <transition>")));
+    }
+
+    let to = vocabulary.state_by_name(target).ok_or_else(
+        || ScxmlError::UnknownState(String::from(target))
+    )?;
+
+    let effects = import_actions(element, vocabulary)?;
+
+    let transition = match element.attribute("cond") {
+        Some(name) => {
+            let (name, condition) = vocabulary.condition_by_name(name).ok_or_else(
+                || ScxmlError::UnknownCondition(String::from(name))
+            )?;
+
+            Transition::with_effects(to, Some(condition), effects).naming(name)
+        },
+        None => Transition::with_effects(to, None, effects)
+    };
+
+    Ok((transition, String::from(target)))
+}
+
+/// Resolves effects of actions inside some element (in order)
+fn import_actions<State, Effect>(
+    element: &XmlElement,
+    vocabulary: &ScxmlVocabulary<State, Effect>
+) -> Result<Vec<Effect>, ScxmlError<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    element.children.iter()
+        .map(|action| match action.local_name() {
+            "send" | "raise" => {
+                let name = action.required_attribute("event")?;
+
+                vocabulary.effect_by_name(name)
+                    .ok_or_else(|| ScxmlError::UnknownEffect(String::from(name)))
+            },
+            _ => Err(ScxmlError::UnexpectedElement(action.name.clone()))
+        })
+        .collect()
+}
+
+/// Resolves effect of at most one action inside <onentry> or <onexit>
+fn import_state_action<State, Effect>(
+    element: &XmlElement,
+    vocabulary: &ScxmlVocabulary<State, Effect>
+) -> Result<Option<Effect>, ScxmlError<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    let mut effects = import_actions(element, vocabulary)?;
+
+    if effects.len() > 1 {
+        return Err(ScxmlError::Unsupported(format!("several actions in <{}>", element.name)));
+    }
+
+    Ok(effects.pop())
+}
+
+fn named_state<State, Effect>(
+    vocabulary: &ScxmlVocabulary<State, Effect>,
+    state: State
+) -> Result<&str, ScxmlError<State>>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    vocabulary.state_name(state).ok_or(ScxmlError::UnnamedState(state))
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch)
+        }
+    }
+
+    escaped
+}
+
+/// Element of parsed XML tree (text content is dropped)
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>
+}
+
+impl XmlElement {
+    fn local_name(&self) -> &str {
+        match self.name.rfind(':') {
+            Some(index) => &self.name[index + 1..],
+            None => &self.name
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn required_attribute<State>(&self, name: &'static str) -> Result<&str, ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        self.attribute(name).ok_or_else(|| ScxmlError::MissingAttribute {
+            element: self.name.clone(),
+            attribute: name
+        })
+    }
+}
+
+/// Minimal XML reader which is enough for SCXML documents:
+/// elements, attributes, comments, processing instructions,
+/// doctype, CDATA and predefined entities
+struct XmlParser<'s> {
+    source: &'s str,
+    position: usize
+}
+
+impl<'s> XmlParser<'s> {
+    fn new(source: &'s str) -> Self {
+        Self {
+            source,
+            position: 0
+        }
+    }
+
+    fn parse_document<State>(&mut self) -> Result<XmlElement, ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        self.skip_misc()?;
+        let root = self.parse_element(1)?;
+        self.skip_misc()?;
+
+        if self.position < self.source.len() {
+            return Err(self.error("content after root element"));
+        }
+
+        Ok(root)
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.source[self.position..]
+    }
+
+    fn error<State>(&self, reason: &'static str) -> ScxmlError<State>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        ScxmlError::Syntax {
+            position: self.position,
+            reason
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past<State>(&mut self, terminator: &str) -> Result<(), ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        match self.rest().find(terminator) {
+            Some(index) => {
+                self.position += index + terminator.len();
+                Ok(())
+            },
+            None => Err(self.error("unterminated markup"))
+        }
+    }
+
+    fn skip_misc<State>(&mut self) -> Result<(), ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_name<State>(&mut self) -> Result<String, ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        let rest = self.rest();
+        let length = rest
+            .find(|ch: char| ch.is_whitespace() || ch == '/' || ch == '>' || ch == '=')
+            .unwrap_or(rest.len());
+
+        if length == 0 {
+            return Err(self.error("expected name"));
+        }
+
+        self.position += length;
+
+        Ok(String::from(&rest[..length]))
+    }
+
+    fn expect<State>(&mut self, token: &str) -> Result<(), ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            Ok(())
+        } else {
+            Err(self.error("unexpected character"))
+        }
+    }
+
+    fn parse_element<State>(&mut self, depth: usize) -> Result<XmlElement, ScxmlError<State>>
+        where State: Eq + PartialEq + Copy + Hash + Debug
+    {
+        if depth > MAX_DEPTH {
+            return Err(self.error("elements are nested too deeply"));
+        }
+
+        self.expect("<")?;
+
+        let mut element = XmlElement {
+            name: self.parse_name()?,
+            attributes: Vec::new(),
+            children: Vec::new()
+        };
+
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            }
+
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+
+            let key = self.parse_name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+
+            let quote = match self.rest().chars().next() {
+                Some(quote) if quote == '"' || quote == '\'' => quote,
+                _ => return Err(self.error("expected quoted attribute value"))
+            };
+
+            self.position += 1;
+
+            let length = match self.rest().find(quote) {
+                Some(length) => length,
+                None => return Err(self.error("unterminated attribute value"))
+            };
+
+            let value = unescape(&self.rest()[..length]);
+            self.position += length + 1;
+
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            if self.rest().starts_with("</") {
+                self.position += 2;
+
+                if self.parse_name()? != element.name {
+                    return Err(self.error("mismatched closing tag"));
+                }
+
+                self.skip_whitespace();
+                self.expect(">")?;
+
+                return Ok(element);
+            }
+
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<![CDATA[") {
+                self.skip_past("]]>")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with('<') {
+                let child = self.parse_element(depth + 1)?;
+                element.children.push(child);
+            } else {
+                match self.rest().find('<') {
+                    Some(length) => self.position += length,
+                    None => return Err(self.error("unclosed element"))
+                }
+            }
+        }
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,272 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::ops::Range;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::map::Set;
+use crate::fsm::{FSM, FSMError};
+
+/// Which of accepted prefixes starting at the same position is reported
+/// (by non-overlapping search)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MatchKind {
+    /// Priority order: run goes the way "FSM::proceed" does (the first transition 
+    /// that accepts character wins) and match ends at the last position 
+    /// where run is in accepting state before it's rejected or input ends
+    LeftmostFirst,
+    /// The longest match among runs through every transition that accepts character
+    /// (not only the first one), so it differs from "LeftmostFirst" only if conditions
+    /// of transitions of some state overlap
+    LeftmostLongest,
+    /// The first position where run (the same as for "LeftmostFirst") 
+    /// reaches accepting state (i.e. the shortest match)
+    LeftmostShortest
+}
+
+/// Unanchored search of pieces of text accepted by FSM
+/// (run from initial state that reaches accepting state).
+/// FSM is run again from every position where match may start,
+/// so search takes O(n^2) steps for text of n characters in the worst case
+/// (when runs from most positions go on up to the end of text).
+/// To scan logs, search every line on its own
+/// or make FSM reject characters that can't be inside match (e.g. newlines),
+/// so runs stay short.
+/// Effects of FSM aren't dispatched
+pub struct Searcher<'f, State, Effect = ()>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    fsm: &'f FSM<State, Effect>,
+    accepting: Set<State>
+}
+
+/// Iterator over byte ranges of matches (see "Searcher::find_iter")
+pub struct Matches<'f, 's, State, Effect>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    searcher: &'s Searcher<'f, State, Effect>,
+    text: &'s str,
+    kind: MatchKind,
+    is_overlapping: bool,
+    /// Byte position where next match may start
+    position: usize,
+    /// Found matches that aren't returned yet (the last one is the next)
+    pending: Vec<Range<usize>>
+}
+
+impl<'f, State, Effect> Searcher<'f, State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    /// Creates new searcher
+    /// - fsm: recognizer of matches,
+    /// - accepting: states where matches end
+    pub fn new<'a>(
+        fsm: &'f FSM<State, Effect>,
+        accepting: Set<State>
+    ) -> Result<Self, FSMError<'a, State>> {
+        for state in accepting.iter() {
+            if fsm.transitions(*state).is_none() {
+                return Err(FSMError::StateDoesNotExist(*state));
+            }
+        }
+
+        Ok(Self {
+            fsm,
+            accepting
+        })
+    }
+
+    /// Finds matches in text from left to right
+    /// (in O(n^2) steps for text of n characters in the worst case, see "Searcher").
+    /// Non-overlapping search goes on after the end of every match,
+    /// overlapping search reports every piece of text accepted by run 
+    /// in priority order (ordered by start and then by end), 
+    /// so kind of matches doesn't matter for it.
+    /// Empty matches aren't reported
+    /// - text: searched text,
+    /// - kind: which of matches starting at the same position is reported
+    ///   (by non-overlapping search),
+    /// - is_overlapping: whether matches may overlap
+    pub fn find_iter<'s>(
+        &'s self,
+        text: &'s str,
+        kind: MatchKind,
+        is_overlapping: bool
+    ) -> Matches<'f, 's, State, Effect> {
+        Matches {
+            searcher: self,
+            text,
+            kind,
+            is_overlapping,
+            position: 0,
+            pending: Vec::new()
+        }
+    }
+
+    /// Finds the first match in text
+    /// - text: searched text,
+    /// - kind: which of matches starting at the same position is reported
+    pub fn find(&self, text: &str, kind: MatchKind) -> Option<Range<usize>> {
+        self.find_iter(text, kind, false).next()
+    }
+
+    /// Checks whether text has any match
+    /// - text: searched text
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text, MatchKind::LeftmostFirst).is_some()
+    }
+}
+
+impl<'f, 's, State, Effect> Iterator for Matches<'f, 's, State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(range) = self.pending.pop() {
+                return Some(range);
+            }
+
+            if self.position >= self.text.len() {
+                return None;
+            }
+
+            let start = self.position;
+            let rest = &self.text[start..];
+            let searcher = self.searcher;
+            let is_accepting = |state| searcher.accepting.contains(&state);
+            let next_char = rest.chars().next().map_or(1, |ch| ch.len_utf8());
+
+            if self.is_overlapping {
+                let pending = &mut self.pending;
+
+                run_prefixes(searcher.fsm, rest, is_accepting, |length, _| {
+                    pending.push(start..start + length);
+                    true
+                });
+
+                self.pending.reverse();
+                self.position = start + next_char;
+            } else {
+                match match_prefix(searcher.fsm, rest, is_accepting, self.kind) {
+                    Some((length, _)) => {
+                        self.position = start + length;
+                        return Some(start..start + length);
+                    },
+                    None => self.position = start + next_char
+                }
+            }
+        }
+    }
+}
+
+/// Runs FSM from its initial state over prefix of input
+/// and returns length (in bytes) of non-empty accepted prefix with its last state
+/// - fsm: recognizer,
+/// - input: text that prefix is taken from,
+/// - is_accepting: checks whether state is accepting,
+/// - kind: which of accepted prefixes is returned
+pub(crate) fn match_prefix<State, Effect>(
+    fsm: &FSM<State, Effect>,
+    input: &str,
+    is_accepting: impl Fn(State) -> bool,
+    kind: MatchKind
+) -> Option<(usize, State)>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    if kind == MatchKind::LeftmostLongest {
+        return longest_prefix(fsm, input, is_accepting);
+    }
+
+    let mut last_accepted = None;
+
+    run_prefixes(fsm, input, is_accepting, |length, state| {
+        last_accepted = Some((length, state));
+        kind == MatchKind::LeftmostFirst
+    });
+
+    last_accepted
+}
+
+/// Runs FSM from its initial state over prefix of input through every transition
+/// that accepts character and returns length (in bytes) of the longest 
+/// non-empty accepted prefix with its last state
+/// (of the first run in priority order that accepts it)
+/// - fsm: recognizer,
+/// - input: text that prefix is taken from,
+/// - is_accepting: checks whether state is accepting
+fn longest_prefix<State, Effect>(
+    fsm: &FSM<State, Effect>,
+    input: &str,
+    is_accepting: impl Fn(State) -> bool
+) -> Option<(usize, State)>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    // distinct runs (states with their registers) in priority order
+    let mut runs = vec![(fsm.start_state(), Vec::new())];
+    let mut longest = None;
+
+    for (position, ch) in input.char_indices() {
+        let mut next_runs = Vec::new();
+
+        for (state, registers) in runs.iter() {
+            fsm.next_states(*state, ch, registers, |state, registers| {
+                let run = (state, registers);
+
+                if !next_runs.contains(&run) {
+                    next_runs.push(run);
+                }
+            });
+        }
+
+        if next_runs.is_empty() {
+            break;
+        }
+
+        if let Some((state, _)) = next_runs.iter().find(|(state, _)| is_accepting(*state)) {
+            longest = Some((position + ch.len_utf8(), *state));
+        }
+
+        runs = next_runs;
+    }
+
+    longest
+}
+
+/// Runs FSM from its initial state over prefix of input (in priority order)
+/// and reports every non-empty accepted prefix (shortest first)
+/// until run is rejected, input ends or "report" returns false
+/// - fsm: recognizer,
+/// - input: text that prefixes are taken from,
+/// - is_accepting: checks whether state is accepting,
+/// - report: takes length (in bytes) of prefix with its last state
+///   and checks whether run goes on
+fn run_prefixes<State, Effect>(
+    fsm: &FSM<State, Effect>,
+    input: &str,
+    is_accepting: impl Fn(State) -> bool,
+    mut report: impl FnMut(usize, State) -> bool
+)
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    let mut state = fsm.start_state();
+    let mut registers = Vec::new();
+
+    for (position, ch) in input.char_indices() {
+        state = match fsm.next_state(state, ch, &mut registers) {
+            Some(state) => state,
+            None => break
+        };
+
+        if is_accepting(state) && !report(position + ch.len_utf8(), state) {
+            break;
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use alloc::string::String;
+use crate::types::{Predicate, Effector, Control, StreamData};
+use crate::fsm::{FSMError, EMPTY_STRING, dispatch_all};
+
+/// State of static FSM that points to its row in table
+pub trait StateIndex: Copy {
+    /// Returns position of row of state (e.g. "self as usize" for enum)
+    fn index(self) -> usize;
+}
+
+/// Transition of static FSM (see "Transition").
+/// It has no guard and registers, so it's built in const context
+pub struct StaticTransition<State, Effect, Input = char>
+    where Effect: 'static
+{
+    /// Item that current character of stream must be equal to.
+    /// If None then any character may pass
+    pub symbol: Option<Input>,
+    /// Predicate that validates current character of stream.
+    /// If None then transition is unconditional
+    pub condition: Option<Predicate<Input>>,
+    /// Next state
+    pub to: State,
+    /// Side effects that are generated (in order) after successful validation of transition
+    pub effects: &'static [Effect]
+}
+
+/// Row of static FSM: transitions and effects of single state
+pub struct StaticRow<State, Effect, Input = char>
+    where State: 'static,
+          Effect: 'static,
+          Input: 'static
+{
+    /// Transitions of state (the first matched one is taken)
+    pub transitions: &'static [StaticTransition<State, Effect, Input>],
+    /// Side effects that occur on entering state
+    pub entry_effects: &'static [Effect],
+    /// Side effects that occur on leaving state
+    pub exit_effects: &'static [Effect]
+}
+
+/// Finite state machine over static tables (e.g. for firmware and hot paths):
+/// rows of states are indexed by states (see "StateIndex")
+/// and everything is borrowed, so machine is built in const context
+/// and runs without heap allocations.
+/// Runs go the same way as runs of "FSM" without nested states.
+/// Tables aren't checked while machine is built, so call "validate" before runs:
+/// otherwise run fails with "StateDoesNotExist" only when it reaches state without row
+pub struct StaticFSM<State, Effect, Error = (), Input = char>
+    where State: 'static,
+          Effect: 'static,
+          Input: 'static
+{
+    /// State at beginning of running through stream
+    initial_state: State,
+    /// Row of every state
+    rows: &'static [StaticRow<State, Effect, Input>],
+    /// Side effects that occur (in order) after proceeding last character
+    post_effects: &'static [Effect],
+    error: PhantomData<fn() -> Error>
+}
+
+impl<State, Effect, Input> StaticTransition<State, Effect, Input>
+    where State: Copy,
+          Input: Copy + PartialEq
+{
+    /// Creates new transition
+    /// - to: next state,
+    /// - condition: predicate for character,
+    /// - effects: side effects (dispatched in order)
+    pub const fn new(to: State, condition: Option<Predicate<Input>>, effects: &'static [Effect]) -> Self {
+        Self {
+            symbol: None,
+            condition,
+            to,
+            effects
+        }
+    }
+
+    /// Creates new transition that matches only given character
+    /// - to: next state,
+    /// - symbol: matched character,
+    /// - effects: side effects (dispatched in order)
+    pub const fn literal(to: State, symbol: Input, effects: &'static [Effect]) -> Self {
+        Self {
+            symbol: Some(symbol),
+            condition: None,
+            to,
+            effects
+        }
+    }
+
+    /// Matches next state and side effects for current character
+    /// - ch: current character (of stream)
+    pub fn transit(&self, ch: Input) -> Option<(State, &'static [Effect])> {
+        let is_valid = self.symbol.is_none_or(|symbol| symbol == ch) && match self.condition {
+            Some(condition) => condition(ch),
+            None => true
+        };
+
+        if is_valid {
+            Some((self.to, self.effects))
+        } else {
+            None
+        }
+    }
+}
+
+impl<State, Effect, Input> StaticRow<State, Effect, Input> {
+    /// Creates row without state effects
+    /// - transitions: transitions of state
+    pub const fn new(transitions: &'static [StaticTransition<State, Effect, Input>]) -> Self {
+        Self {
+            transitions,
+            entry_effects: &[],
+            exit_effects: &[]
+        }
+    }
+
+    /// Sets effects that occur on entering state
+    /// - effects: side effects (dispatched in order)
+    pub const fn entering(mut self, effects: &'static [Effect]) -> Self {
+        self.entry_effects = effects;
+        self
+    }
+
+    /// Sets effects that occur on leaving state
+    /// - effects: side effects (dispatched in order)
+    pub const fn leaving(mut self, effects: &'static [Effect]) -> Self {
+        self.exit_effects = effects;
+        self
+    }
+}
+
+impl<State, Effect, Error, Input> StaticFSM<State, Effect, Error, Input> {
+    /// Creates new instance of static FSM.
+    /// States aren't checked (it's const context), so call "validate" before runs
+    /// - initial_state: starting state,
+    /// - rows: row of every state (in order of indices of states),
+    /// - post_effects: post-effects
+    pub const fn new(
+        initial_state: State,
+        rows: &'static [StaticRow<State, Effect, Input>],
+        post_effects: &'static [Effect]
+    ) -> Self {
+        Self {
+            initial_state,
+            rows,
+            post_effects,
+            error: PhantomData
+        }
+    }
+}
+
+impl<State, Effect, Error, Input> StaticFSM<State, Effect, Error, Input>
+    where State: Eq + PartialEq + Copy + Debug + StateIndex,
+          Effect: Clone,
+          Input: Copy + PartialEq
+{
+    /// Returns starting state
+    pub fn initial_state(&self) -> State {
+        self.initial_state
+    }
+
+    /// Returns row of state
+    /// - state: state of FSM
+    pub fn row(&self, state: State) -> Option<&'static StaticRow<State, Effect, Input>> {
+        self.rows.get(state.index())
+    }
+
+    /// Checks that initial state and targets of every transition have rows
+    /// (states that effector goes to are checked during runs)
+    pub fn validate<'a>(&self) -> Result<(), FSMError<'a, State, Error, Input>> {
+        let targets = self.rows.iter()
+            .flat_map(|row| row.transitions.iter())
+            .map(|transition| transition.to);
+
+        for state in Some(self.initial_state).into_iter().chain(targets) {
+            if self.row(state).is_none() {
+                return Err(FSMError::StateDoesNotExist(state));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs items of some input through FSM (see "FSM::proceed_iter")
+    /// - inputs: runnable items,
+    /// - effector: module that mutates some data by effects
+    pub fn proceed_iter<'a, I>(
+        &self,
+        inputs: I,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>>
+        where I: IntoIterator<Item = Input>
+    {
+        self.run(&EMPTY_STRING, inputs.into_iter(), effector)
+    }
+
+    /// Runs input items: effects are dispatched and controlled by effector
+    /// like in "FSM::proceed"
+    fn run<'a, I>(
+        &self,
+        string: &'a String,
+        inputs: I,
+        mut effector: Option<&'a mut dyn Effector<Effect, State, Error, Input>>
+    ) -> Result<(), FSMError<'a, State, Error, Input>>
+        where I: Iterator<Item = Input>
+    {
+        let mut state = self.initial_state;
+
+        let control = dispatch_all(
+            &mut effector,
+            self.row(state).map_or(&[][..], |row| row.entry_effects).iter(),
+            StreamData {
+                string,
+                index: 0,
+                character: None
+            },
+            None
+        );
+
+        match control {
+            Control::Continue => {},
+            Control::Stop => return Ok(()),
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(next) => state = self.target(next)?
+        }
+
+        let mut length = 0;
+
+        for (index, ch) in inputs.enumerate() {
+            length = index + 1;
+
+            let row = self.row(state).ok_or(FSMError::StateDoesNotExist(state))?;
+            let input_data = StreamData {
+                string,
+                index,
+                character: Some(ch)
+            };
+
+            let (to, effects) = match row.transitions.iter().find_map(|transition| transition.transit(ch)) {
+                Some(matched) => matched,
+                None => return Err(FSMError::NoValidTransition {
+                    from: state,
+                    input_data
+                })
+            };
+
+            // state effects aren't dispatched for self-loops
+            let (exit_effects, entry_effects) = if to == state {
+                (&[][..], &[][..])
+            } else {
+                (row.exit_effects, self.row(to).map_or(&[][..], |row| row.entry_effects))
+            };
+
+            let control = dispatch_all(
+                &mut effector,
+                exit_effects.iter().chain(effects.iter()).chain(entry_effects.iter()),
+                input_data,
+                None
+            );
+
+            state = to;
+
+            match control {
+                Control::Continue => {},
+                Control::Stop => return Ok(()),
+                Control::Error(error) => return Err(FSMError::Effect(error)),
+                Control::GoTo(next) => state = self.target(next)?
+            }
+        }
+
+        let control = dispatch_all(
+            &mut effector,
+            self.row(state).map_or(&[][..], |row| row.exit_effects).iter()
+                .chain(self.post_effects.iter()),
+            StreamData {
+                string,
+                index: string.len().max(length),
+                character: None
+            },
+            None
+        );
+
+        match control {
+            Control::Error(error) => Err(FSMError::Effect(error)),
+            Control::GoTo(next) => self.target(next).map(|_| ()),
+            _ => Ok(())
+        }
+    }
+
+    /// Checks state that effector goes to
+    /// - state: next state
+    fn target<'a>(&self, state: State) -> Result<State, FSMError<'a, State, Error, Input>> {
+        match self.row(state) {
+            Some(_) => Ok(state),
+            None => Err(FSMError::StateDoesNotExist(state))
+        }
+    }
+}
+
+/// Runs through strings (for FSM over characters)
+impl<State, Effect, Error> StaticFSM<State, Effect, Error>
+    where State: Eq + PartialEq + Copy + Debug + StateIndex,
+          Effect: Clone
+{
+    /// Runs some string through FSM to validate it (and apply some effects)
+    /// like "FSM::proceed" does
+    /// - string: runnable string,
+    /// - effector: module that mutates some data by effects
+    pub fn proceed<'a>(
+        &self,
+        string: &'a String,
+        effector: Option<&'a mut dyn Effector<Effect, State, Error>>
+    ) -> Result<(), FSMError<'a, State, Error>> {
+        self.run(string, string.chars(), effector)
+    }
+}
@@ -0,0 +1,347 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use core::future::poll_fn;
+use core::pin::{Pin, pin};
+use core::task::{Context as TaskContext, Poll, ready};
+use std::io;
+use alloc::vec::Vec;
+use futures_core::Stream;
+use futures_io::AsyncBufRead;
+use crate::types::{Effector, Control, StreamData};
+use crate::fsm::{FSM, FSMError, EMPTY_STRING};
+use crate::runner::Runner;
+
+/// Executor of side effects that may wait (e.g. for I/O).
+/// Futures of dispatches aren't required to be Send,
+/// so runs may go on local executors
+#[allow(async_fn_in_trait)]
+pub trait AsyncEffector<Effect, State, Error = (), Input = char, Context = ()>
+    where Effect: Clone
+{
+    /// Applies side effect (see "Effector::dispatch")
+    /// - effect: side effect,
+    /// - input_data: additional dependencies for effects
+    async fn dispatch(&mut self, effect: Effect, input_data: StreamData<'_, Input>) -> Control<State, Error>;
+
+    /// Returns data read by guards of transitions before every item
+    /// (so it reflects every applied effect).
+    /// Guarded transitions never match if there's none (by default)
+    fn context(&self) -> Option<&Context> {
+        None
+    }
+}
+
+/// Error of run over characters of reader
+#[derive(Debug)]
+pub enum StreamError<State, Error = ()>
+    where State: Eq + PartialEq + Copy + Hash + Debug
+{
+    /// Error of run
+    Run(FSMError<'static, State, Error>),
+    /// Error of reader (invalid UTF-8 is "InvalidData")
+    Read(io::Error)
+}
+
+/// Stream of characters of UTF-8 text of async reader
+pub struct Utf8Chars<R>
+    where R: AsyncBufRead + Unpin
+{
+    reader: R,
+    /// Bytes of incomplete character
+    pending: [u8; 4],
+    /// Number of pending bytes
+    pending_len: usize
+}
+
+/// Effector that collects effects of single step to dispatch them later
+/// (with context of async effector for guards)
+struct Recorder<'r, Effect, Context> {
+    effects: &'r mut Vec<Effect>,
+    context: Option<&'r Context>
+}
+
+/// Run that dispatches effects of every step by async effector
+struct AsyncRun<'f, 'e, State, Effect, Error, Context, Input, E>
+    where State: Eq + PartialEq + Copy + Hash,
+          Effect: Clone
+{
+    runner: Runner<'f, State, Effect, Error, Context, Input>,
+    /// Recorded effects of current step
+    effects: Vec<Effect>,
+    effector: Option<&'e mut E>
+}
+
+/// Async runs (with "async" feature)
+impl<State, Effect, Error, Context, Input> FSM<State, Effect, Error, Context, Input>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone,
+          Input: Copy + PartialEq
+{
+    /// Runs items of async stream through FSM like "proceed_iter" does,
+    /// but effector may wait while it applies effects
+    /// (and it may provide context for guards, see "AsyncEffector::context").
+    /// Input data of effects holds empty string
+    /// (None effector needs its type, e.g. "None::<&mut MyEffector>").
+    /// Returns last state (like "Runner::finish")
+    /// - stream: runnable items,
+    /// - effector: module that mutates some data by effects
+    pub async fn proceed_stream<S, E>(
+        &self,
+        stream: S,
+        effector: Option<&mut E>
+    ) -> Result<State, FSMError<'static, State, Error, Input>>
+        where S: Stream<Item = Input>,
+              E: AsyncEffector<Effect, State, Error, Input, Context>
+    {
+        let mut stream = pin!(stream);
+        let mut run = AsyncRun::start(self, effector).await?;
+
+        while !run.runner.is_finished() {
+            match poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                Some(item) => run.feed(item).await?,
+                None => break
+            }
+        }
+
+        run.finish().await
+    }
+}
+
+/// Async runs through readers (for FSM over characters)
+impl<State, Effect, Error, Context> FSM<State, Effect, Error, Context>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone
+{
+    /// Runs UTF-8 text of async reader through FSM like "proceed_stream" does
+    /// (reading stops as soon as run is over) and returns last state
+    /// - reader: source of text,
+    /// - effector: module that mutates some data by effects
+    pub async fn proceed_reader<R, E>(
+        &self,
+        reader: R,
+        effector: Option<&mut E>
+    ) -> Result<State, StreamError<State, Error>>
+        where R: AsyncBufRead + Unpin,
+              E: AsyncEffector<Effect, State, Error, char, Context>
+    {
+        let mut chars = Utf8Chars::new(reader);
+        let mut run = AsyncRun::start(self, effector).await.map_err(StreamError::Run)?;
+
+        while !run.runner.is_finished() {
+            match poll_fn(|cx| Pin::new(&mut chars).poll_next(cx)).await {
+                Some(Ok(ch)) => run.feed(ch).await.map_err(StreamError::Run)?,
+                Some(Err(error)) => return Err(StreamError::Read(error)),
+                None => break
+            }
+        }
+
+        run.finish().await.map_err(StreamError::Run)
+    }
+}
+
+impl<'f, 'e, State, Effect, Error, Context, Input, E> AsyncRun<'f, 'e, State, Effect, Error, Context, Input, E>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone,
+          Input: Copy + PartialEq,
+          E: AsyncEffector<Effect, State, Error, Input, Context>
+{
+    /// Starts run (dispatches entry effects of initial state)
+    async fn start(
+        fsm: &'f FSM<State, Effect, Error, Context, Input>,
+        effector: Option<&'e mut E>
+    ) -> Result<Self, FSMError<'static, State, Error, Input>> {
+        let mut effects = Vec::new();
+        let runner = Runner::start(fsm, Some(&mut Recorder::<_, Context>::new(&mut effects)))?;
+
+        let mut run = Self {
+            runner,
+            effects,
+            effector
+        };
+
+        match run.dispatch(0, None).await {
+            Control::Continue => {},
+            Control::Stop => run.runner.stop(),
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(state) => run.runner.go_to(state)?
+        }
+
+        Ok(run)
+    }
+
+    /// Proceeds next item
+    async fn feed(&mut self, item: Input) -> Result<(), FSMError<'static, State, Error, Input>> {
+        let index = self.runner.len();
+        let mut recorder = Recorder {
+            effects: &mut self.effects,
+            context: self.effector.as_deref().and_then(|effector| effector.context())
+        };
+
+        self.runner.feed_with(item, Some(&mut recorder), |recorder| recorder.context)?;
+
+        match self.dispatch(index, Some(item)).await {
+            Control::Continue => {},
+            Control::Stop => self.runner.stop(),
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(state) => self.runner.go_to(state)?
+        }
+
+        Ok(())
+    }
+
+    /// Finishes run after last item (like "FSM::end", effector may go to other state)
+    /// and returns last state
+    async fn finish(mut self) -> Result<State, FSMError<'static, State, Error, Input>> {
+        let index = self.runner.len();
+        self.runner.end(Some(&mut Recorder::<_, Context>::new(&mut self.effects)))?;
+
+        match self.dispatch(index, None).await {
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(state) => self.runner.go_to(state)?,
+            _ => {}
+        }
+
+        Ok(self.runner.state())
+    }
+
+    /// Dispatches recorded effects
+    async fn dispatch(&mut self, index: usize, character: Option<Input>) -> Control<State, Error> {
+        dispatch_recorded(&mut self.effects, &mut self.effector, index, character).await
+    }
+}
+
+/// Dispatches recorded effects in order until effector returns anything but "Continue"
+/// (the rest of effects is dropped)
+/// - effects: effects of step,
+/// - effector: module that mutates some data by effects,
+/// - index: position of item,
+/// - character: item (None before first item and after last one)
+async fn dispatch_recorded<Effect, State, Error, Input, Context, E>(
+    effects: &mut Vec<Effect>,
+    effector: &mut Option<&mut E>,
+    index: usize,
+    character: Option<Input>
+) -> Control<State, Error>
+    where Effect: Clone,
+          Input: Copy,
+          E: AsyncEffector<Effect, State, Error, Input, Context>
+{
+    for effect in effects.drain(..) {
+        if let Some(effector) = effector.as_mut() {
+            let input_data = StreamData {
+                string: &EMPTY_STRING,
+                index,
+                character
+            };
+
+            match effector.dispatch(effect, input_data).await {
+                Control::Continue => {},
+                control => return control
+            }
+        }
+    }
+
+    Control::Continue
+}
+
+impl<'r, Effect, Context> Recorder<'r, Effect, Context> {
+    /// Creates recorder without context
+    /// - effects: recorded effects
+    fn new(effects: &'r mut Vec<Effect>) -> Self {
+        Self {
+            effects,
+            context: None
+        }
+    }
+}
+
+impl<Effect, State, Error, Input, Context> Effector<Effect, State, Error, Input> for Recorder<'_, Effect, Context>
+    where Effect: Clone
+{
+    fn dispatch(&mut self, effect: Effect, _input_data: StreamData<Input>) -> Control<State, Error> {
+        self.effects.push(effect);
+        Control::Continue
+    }
+}
+
+impl<R> Utf8Chars<R>
+    where R: AsyncBufRead + Unpin
+{
+    /// Creates stream of characters
+    /// - reader: source of text
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: [0; 4],
+            pending_len: 0
+        }
+    }
+
+    /// Returns reader back (with bytes that aren't read yet)
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Decodes pending bytes: character (if they make one),
+    /// None (if character is incomplete) or error
+    fn decode(&mut self) -> Option<io::Result<char>> {
+        let expected_len = match self.pending[0] {
+            0x00..=0x7F => 1,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => 0
+        };
+
+        if self.pending_len < expected_len {
+            return None;
+        }
+
+        let bytes = &self.pending[..self.pending_len];
+        self.pending_len = 0;
+
+        match core::str::from_utf8(bytes).ok().and_then(|text| text.chars().next()) {
+            Some(ch) => Some(Ok(ch)),
+            None => Some(Err(invalid_utf8()))
+        }
+    }
+}
+
+impl<R> Stream for Utf8Chars<R>
+    where R: AsyncBufRead + Unpin
+{
+    type Item = io::Result<char>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            let buffer = match ready!(Pin::new(&mut this.reader).poll_fill_buf(cx)) {
+                Ok(buffer) => buffer,
+                Err(error) => return Poll::Ready(Some(Err(error)))
+            };
+
+            let byte = match buffer.first() {
+                Some(byte) => *byte,
+                // text ends inside character
+                None if this.pending_len > 0 => {
+                    this.pending_len = 0;
+                    return Poll::Ready(Some(Err(invalid_utf8())));
+                },
+                None => return Poll::Ready(None)
+            };
+
+            Pin::new(&mut this.reader).consume(1);
+            this.pending[this.pending_len] = byte;
+            this.pending_len += 1;
+
+            if let Some(decoded) = this.decode() {
+                return Poll::Ready(Some(decoded));
+            }
+        }
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
@@ -1,9 +1,9 @@
 use std::hash::Hash;
-use std::collections::HashMap;
+use crate::map::Map;
 use std::fmt::Debug;
 use crate::fsm::FSM;
 use crate::types::{Transition, StatesConnection};
-use super::super::utils::{is_digit, is_letter};
+use super::super::utils::{is_digit, is_letter, is_dot};
 
 #[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
 pub enum State {
@@ -14,7 +14,7 @@ pub enum State {
 }
 
 pub fn init_fsm<Effect>(
-    effects_map: Option<&HashMap<StatesConnection<State>, Vec<Effect>>>,
+    effects_map: Option<&Map<StatesConnection<State>, Vec<Vec<Effect>>>>,
     post_effect: Option<Effect>
 ) -> FSM<State, Effect> 
     where Effect: Eq + PartialEq + Copy
@@ -27,12 +27,12 @@ pub fn init_fsm<Effect>(
                     State::WORD,
                     Some(is_letter),
                     None
-                ),
+                ).naming("is_letter"),
                 Transition::new(
                     State::NUMBER_IP,
                     Some(is_digit),
                     None
-                ),
+                ).naming("is_digit"),
                 Transition::new(
                     State::INIT,
                     None,
@@ -44,12 +44,12 @@ pub fn init_fsm<Effect>(
                     State::WORD,
                     Some(is_letter),
                     None
-                ),
+                ).naming("is_letter"),
                 Transition::new(
                     State::NUMBER_IP,
                     Some(is_digit),
                     None
-                ),
+                ).naming("is_digit"),
                 Transition::new(
                     State::INIT,
                     None,
@@ -61,19 +61,17 @@ pub fn init_fsm<Effect>(
                     State::WORD,
                     Some(is_letter),
                     None
-                ),
+                ).naming("is_letter"),
                 Transition::new(
                     State::NUMBER_IP,
                     Some(is_digit),
                     None
-                ),
+                ).naming("is_digit"),
                 Transition::new(
                     State::NUMBER_FP,
-                    Some(|ch| {
-                        ch == '.'
-                    }),
+                    Some(is_dot),
                     None
-                ),
+                ).naming("is_dot"),
                 Transition::new(
                     State::INIT,
                     None,
@@ -85,12 +83,12 @@ pub fn init_fsm<Effect>(
                     State::WORD,
                     Some(is_letter),
                     None
-                ),
+                ).naming("is_letter"),
                 Transition::new(
                     State::NUMBER_FP,
                     Some(is_digit),
                     None
-                ),
+                ).naming("is_digit"),
                 Transition::new(
                     State::INIT,
                     None,
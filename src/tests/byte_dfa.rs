@@ -0,0 +1,147 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, StreamData};
+use crate::byte_dfa::ByteDFA;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Key,
+    Value,
+    Space,
+    Word
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    Separator,
+    End,
+    StartWord,
+    EndWord
+}
+
+fn is_key_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+}
+
+fn is_value_byte(byte: u8) -> bool {
+    byte != b';'
+}
+
+fn is_alphabetic(ch: char) -> bool {
+    ch.is_alphabetic()
+}
+
+/// Parses pairs like "key=value;"
+fn setup_pairs() -> FSM<State, Effect, (), (), u8> {
+    FSM::new(
+        State::Key,
+        map!(
+            State::Key => vec![
+                Transition::new(State::Key, Some(is_key_byte), None),
+                Transition::literal(State::Value, b'=', vec![Effect::Separator])
+            ],
+            State::Value => vec![
+                Transition::literal(State::Key, b';', vec![Effect::End]),
+                Transition::new(State::Value, Some(is_value_byte), None)
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+fn setup_words() -> FSM<State, Effect> {
+    FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_alphabetic), Some(Effect::StartWord)),
+                Transition::literal(State::Space, ' ', vec![])
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_alphabetic), None),
+                Transition::literal(State::Space, ' ', vec![Effect::EndWord])
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+#[test]
+fn it_runs_byte_fsm_by_table() {
+    let fsm = setup_pairs();
+    let dfa = ByteDFA::new(&fsm).unwrap();
+
+    // key bytes, '=', ';' and other bytes
+    assert_eq!(dfa.class_count(), 4);
+    assert_eq!(dfa.node_count(), 2);
+
+    let bytes = b"a=1;bc=\xFF\x00;";
+
+    let mut expected = Vec::new();
+    let mut actual = Vec::new();
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData<u8>| {
+            expected.push((effect, input_data.index));
+        };
+        assert!(fsm.proceed_iter(bytes.iter().copied(), Some(&mut logger)).is_ok());
+    }
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData<u8>| {
+            actual.push((effect, input_data.index));
+        };
+        assert!(dfa.proceed(bytes, Some(&mut logger)).is_ok());
+    }
+
+    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 4);
+
+    assert!(matches!(
+        dfa.proceed(b"a;", None),
+        Err(FSMError::NoValidTransition { from: State::Key, input_data }) if input_data.index == 1
+    ));
+}
+
+#[test]
+fn it_runs_char_fsm_over_utf8() {
+    let dfa = ByteDFA::from_chars(&setup_words()).unwrap();
+
+    let string = "héllo мир 世界";
+
+    let mut journal = Vec::new();
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData<u8>| {
+            journal.push((effect, input_data.index));
+        };
+        assert!(dfa.proceed(string.as_bytes(), Some(&mut logger)).is_ok());
+    }
+
+    // effects are at the last byte of characters
+    assert_eq!(
+        journal,
+        vec![
+            (Effect::StartWord, 0),
+            (Effect::EndWord, 6),
+            (Effect::StartWord, 8),
+            (Effect::EndWord, 13),
+            (Effect::StartWord, 16)
+        ]
+    );
+
+    // invalid byte, surrogate and truncated character
+    assert!(matches!(
+        dfa.proceed(b"a\xFF", None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 1
+    ));
+    assert!(matches!(
+        dfa.proceed(b"a\xED\xA0\x80", None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 2
+    ));
+    assert!(matches!(
+        dfa.proceed(&"мир".as_bytes()[..5], None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 5
+    ));
+}
@@ -0,0 +1,186 @@
+#![cfg(test)]
+
+use crate::fsm::FSM;
+use crate::types::{Transition, StreamData};
+use crate::hierarchy::History;
+use crate::codegen::{Codegen, CodegenError};
+use crate::vocabulary::Vocabulary;
+
+use super::utils::{is_letter, is_digit};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+pub enum State {
+    Init,
+    Token,
+    Word,
+    Number,
+    Resume
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Effect {
+    EnterToken,
+    ExitToken,
+    Letter,
+    Digit,
+    Done
+}
+
+mod generated {
+    use super::{State, Effect};
+    use super::super::utils::{is_letter, is_digit};
+
+    include!("generated/tokens.rs");
+}
+
+fn setup_fsm() -> FSM<State, Effect> {
+    let mut fsm = FSM::new(
+        State::Init,
+        map!(
+            State::Init => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)).naming("is_letter"),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit)).naming("is_digit"),
+                Transition::literal(State::Init, ' ', vec![]),
+                Transition::literal(State::Token, '#', vec![])
+            ],
+            State::Token => vec![
+                Transition::literal(State::Init, ' ', vec![])
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)).naming("is_letter")
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit)).naming("is_digit")
+            ]
+        ),
+        Some(Effect::Done)
+    ).unwrap();
+
+    assert!(fsm.set_parent(State::Word, State::Token).is_ok());
+    assert!(fsm.set_parent(State::Number, State::Token).is_ok());
+    assert!(fsm.set_initial_child(State::Token, State::Word).is_ok());
+    assert!(fsm.set_entry_effect(State::Token, Some(Effect::EnterToken)).is_ok());
+    assert!(fsm.set_exit_effect(State::Token, Some(Effect::ExitToken)).is_ok());
+
+    fsm
+}
+
+fn setup_states() -> Vocabulary<State, Effect> {
+    Vocabulary::new()
+        .state(State::Init, "State::Init")
+        .state(State::Token, "State::Token")
+        .state(State::Word, "State::Word")
+        .state(State::Number, "State::Number")
+}
+
+fn setup_codegen() -> Codegen<State, Effect> {
+    let vocabulary = setup_states()
+        .condition("is_letter", is_letter)
+        .condition("is_digit", is_digit)
+        .effect(Effect::EnterToken, "Effect::EnterToken")
+        .effect(Effect::ExitToken, "Effect::ExitToken")
+        .effect(Effect::Letter, "Effect::Letter")
+        .effect(Effect::Digit, "Effect::Digit")
+        .effect(Effect::Done, "Effect::Done");
+
+    Codegen::new("run_tokens", "State", "Effect", vocabulary).crate_path("crate")
+}
+
+#[test]
+fn it_generates_function_like_fsm() {
+    let fsm = setup_fsm();
+    let code = setup_codegen().generate(&fsm).unwrap();
+
+    // generated code is included by "generated" module
+    assert_eq!(code, include_str!("generated/tokens.rs"));
+
+    for input in ["ab 12 #xy z", "7", "", "a1"].iter() {
+        let string = String::from(*input);
+
+        let mut expected = Vec::new();
+        let mut actual = Vec::new();
+
+        let expected_result = {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                expected.push((effect, input_data.index));
+            };
+
+            fsm.proceed(&string, Some(&mut logger)).map_err(|error| format!("{:?}", error))
+        };
+
+        let actual_result = {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                actual.push((effect, input_data.index));
+            };
+
+            generated::run_tokens(&string, Some(&mut logger)).map_err(|error| format!("{:?}", error))
+        };
+
+        assert_eq!(actual_result, expected_result);
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn it_rejects_parts_that_cant_be_generated() {
+    let without_effects = setup_states().condition("is_letter", is_letter);
+
+    assert_eq!(
+        Codegen::new("run_tokens", "State", "Effect", without_effects).generate(&setup_fsm()),
+        Err(CodegenError::UnnamedEffect { from: State::Init, index: Some(0) })
+    );
+
+    let without_condition = setup_states()
+        .condition("is_letter", is_letter)
+        .effect(Effect::EnterToken, "Effect::EnterToken")
+        .effect(Effect::Letter, "Effect::Letter");
+
+    assert_eq!(
+        Codegen::new("run_tokens", "State", "Effect", without_condition).generate(&setup_fsm()),
+        Err(CodegenError::UnnamedCondition { from: State::Init, index: 1 })
+    );
+
+    // condition is found by name of transition, not by predicate
+    let unnamed: FSM<State, Effect> = FSM::new(
+        State::Init,
+        map!(
+            State::Init => vec![
+                Transition::new(State::Init, Some(is_letter), None)
+            ]
+        ),
+        None
+    ).unwrap();
+
+    assert_eq!(
+        setup_codegen().generate(&unnamed),
+        Err(CodegenError::UnnamedCondition { from: State::Init, index: 0 })
+    );
+
+    let mut with_history = setup_fsm();
+    assert!(with_history.add_history(State::Resume, State::Token, History::Deep).is_ok());
+
+    assert_eq!(
+        setup_codegen().generate(&with_history),
+        Err(CodegenError::History(State::Resume))
+    );
+
+    let guarded: FSM<State, Effect> = FSM::new(
+        State::Init,
+        map!(
+            State::Init => vec![
+                Transition::new(State::Init, Some(is_letter), None).naming("is_letter"),
+                Transition::guarded(State::Init, None, |_, _| true, vec![])
+            ]
+        ),
+        None
+    ).unwrap();
+
+    assert_eq!(
+        Codegen::new("run", "State", "Effect", Vocabulary::new()).generate(&guarded),
+        Err(CodegenError::UnnamedState(State::Init))
+    );
+    assert_eq!(
+        setup_codegen().generate(&guarded),
+        Err(CodegenError::GuardedTransition { from: State::Init, index: 1 })
+    );
+}
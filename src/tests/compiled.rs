@@ -0,0 +1,234 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, StreamData};
+use crate::hierarchy::History;
+use crate::compiled::{CompiledFSM, CompileError};
+
+use super::utils::{is_letter, is_digit};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Init,
+    Token,
+    Word,
+    Number,
+    Resume
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    EnterToken,
+    ExitToken,
+    Letter,
+    Digit,
+    Done
+}
+
+fn is_non_ascii_letter(ch: char) -> bool {
+    ch.is_alphabetic() && !ch.is_ascii()
+}
+
+fn setup_fsm() -> FSM<State, Effect> {
+    let mut fsm = FSM::new(
+        State::Init,
+        map!(
+            State::Init => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Word, Some(is_non_ascii_letter), Some(Effect::Letter)),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit)),
+                Transition::literal(State::Init, ' ', vec![])
+            ],
+            State::Token => vec![
+                Transition::literal(State::Init, ' ', vec![])
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Word, Some(is_non_ascii_letter), Some(Effect::Letter))
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit))
+            ]
+        ),
+        Some(Effect::Done)
+    ).unwrap();
+
+    assert!(fsm.set_parent(State::Word, State::Token).is_ok());
+    assert!(fsm.set_parent(State::Number, State::Token).is_ok());
+    assert!(fsm.set_entry_effect(State::Token, Some(Effect::EnterToken)).is_ok());
+    assert!(fsm.set_exit_effect(State::Token, Some(Effect::ExitToken)).is_ok());
+
+    fsm
+}
+
+#[test]
+fn it_runs_like_source_fsm() {
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+
+    // letters, digits, space and everything else
+    assert_eq!(compiled.class_count(), 4);
+
+    for input in ["ab 12 ψω x", "7", ""].iter() {
+        let string = String::from(*input);
+
+        let mut expected = Vec::new();
+        let mut actual = Vec::new();
+
+        {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                expected.push((effect, input_data.index));
+            };
+            assert!(compiled.fsm().proceed(&string, Some(&mut logger)).is_ok());
+        }
+
+        {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                actual.push((effect, input_data.index));
+            };
+            assert!(compiled.proceed(&string, Some(&mut logger)).is_ok());
+        }
+
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn it_rejects_invalid_input() {
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+    let string = String::from("ab1");
+
+    assert!(matches!(
+        compiled.proceed(&string, None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 2
+    ));
+}
+
+#[test]
+fn it_rejects_fsm_that_depends_on_run() {
+    let guarded: FSM<State, Effect> = FSM::new(
+        State::Init,
+        map!(
+            State::Init => vec![
+                Transition::new(State::Init, Some(is_letter), None),
+                Transition::guarded(State::Init, None, |_, _| true, vec![])
+            ]
+        ),
+        None
+    ).unwrap();
+
+    assert!(matches!(
+        CompiledFSM::new(guarded),
+        Err(CompileError::GuardedTransition { from: State::Init, index: 1 })
+    ));
+
+    let mut with_history = setup_fsm();
+    assert!(with_history.add_history(State::Resume, State::Token, History::Shallow).is_ok());
+
+    assert!(matches!(
+        CompiledFSM::new(with_history),
+        Err(CompileError::History(State::Resume))
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn it_runs_chunks_in_parallel() {
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+    let string = "ab 12 ψω x ".repeat(50) + "7";
+
+    let mut expected = Vec::new();
+    let mut actual = Vec::new();
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            expected.push((effect, input_data.index, input_data.character));
+        };
+        assert!(compiled.proceed(&string, Some(&mut logger)).is_ok());
+    }
+
+    for chunks in [1, 3, 8, 1000].iter() {
+        actual.clear();
+
+        {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                actual.push((effect, input_data.index, input_data.character));
+            };
+            assert!(compiled.proceed_parallel(&string, *chunks, Some(&mut logger)).is_ok());
+        }
+
+        assert_eq!(actual, expected);
+        assert!(compiled.proceed_parallel(&string, *chunks, None).is_ok());
+    }
+
+    let empty = String::new();
+    assert!(compiled.proceed_parallel(&empty, 4, None).is_ok());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn it_stops_parallel_run_before_chunks_are_recorded() {
+    use crate::types::{Effector, Control};
+
+    /// Stops run after given number of effects
+    struct Limit(usize);
+
+    impl Effector<Effect, State> for Limit {
+        fn dispatch(&mut self, _effect: Effect, _input_data: StreamData) -> Control<State> {
+            self.0 -= 1;
+
+            if self.0 == 0 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        }
+    }
+
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+    let string = "ab 12 ".repeat(5000);
+
+    // the first chunk is stopped before threads record later ones,
+    // and threads that wait for dispatch of later chunks must stop with it
+    for effects in [10, 10_000].iter() {
+        let mut limit = Limit(*effects);
+
+        assert!(compiled.proceed_parallel(&string, 8, Some(&mut limit)).is_ok());
+        assert_eq!(limit.0, 0);
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn it_rejects_invalid_input_in_parallel() {
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+    let string = "ab 12 ".repeat(20) + "ab1" + &" 12".repeat(20);
+
+    let mut expected = Vec::new();
+    let mut actual = Vec::new();
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            expected.push((effect, input_data.index));
+        };
+        assert!(compiled.proceed(&string, Some(&mut logger)).is_err());
+    }
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            actual.push((effect, input_data.index));
+        };
+
+        assert!(matches!(
+            compiled.proceed_parallel(&string, 5, Some(&mut logger)),
+            Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 122
+        ));
+    }
+
+    // effects before rejected character are dispatched
+    assert_eq!(actual, expected);
+
+    assert!(matches!(
+        compiled.proceed_parallel(&string, 5, None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 122
+    ));
+}
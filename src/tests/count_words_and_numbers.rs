@@ -1,6 +1,6 @@
 #![cfg(test)]
-    use std::collections::HashMap;
-    use crate::types::{Effector, StreamData, StatesConnection};
+    use crate::map::Map;
+    use crate::types::{Effector, Control, StreamData, StatesConnection};
 
     use super::utils::test_valid_string;
     use super::automatas::words_and_numbers::*;
@@ -39,41 +39,43 @@
         }
     }
 
-    impl Effector<Effect> for Counter {
-        fn dispatch(&mut self, effect: Effect, _data: StreamData) {
+    impl Effector<Effect, State> for Counter {
+        fn dispatch(&mut self, effect: Effect, _data: StreamData) -> Control<State> {
             match effect {
                 Effect::INCREMENT_WORD_COUNT => self.increment_word_count(),
                 Effect::INCREMENT_NUMBER_COUNT => self.increment_number_count()
             }
+
+            Control::Continue
         }
     }
 
-    fn setup_effects() -> HashMap<StatesConnection<State>, Vec<Effect>> {
+    fn setup_effects() -> Map<StatesConnection<State>, Vec<Vec<Effect>>> {
         map!(
             StatesConnection { 
                 from: State::INIT,
                 to: State::WORD
-            } => vec![Effect::INCREMENT_WORD_COUNT],
+            } => vec![vec![Effect::INCREMENT_WORD_COUNT]],
 
             StatesConnection {
                 from: State::INIT,
                 to: State::NUMBER_IP
-            } => vec![Effect::INCREMENT_NUMBER_COUNT],
+            } => vec![vec![Effect::INCREMENT_NUMBER_COUNT]],
 
             StatesConnection {
                 from: State::WORD,
                 to: State::NUMBER_IP
-            } => vec![Effect::INCREMENT_NUMBER_COUNT],
+            } => vec![vec![Effect::INCREMENT_NUMBER_COUNT]],
 
             StatesConnection {
                 from: State::NUMBER_IP,
                 to: State::WORD
-            } => vec![Effect::INCREMENT_WORD_COUNT],
+            } => vec![vec![Effect::INCREMENT_WORD_COUNT]],
 
             StatesConnection {
                 from: State::NUMBER_FP,
                 to: State::WORD
-            } => vec![Effect::INCREMENT_WORD_COUNT],
+            } => vec![vec![Effect::INCREMENT_WORD_COUNT]],
         )
     }
 
@@ -0,0 +1,222 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, Effector, Control, StreamData};
+use crate::trace::Trace;
+use crate::runner::Runner;
+#[cfg(feature = "std")]
+use crate::compiled::CompiledFSM;
+
+use super::utils::is_letter;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Space,
+    Word,
+    Comment
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    PushLetter,
+    Separate
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum LimitError {
+    TooLongWord {
+        index: usize
+    }
+}
+
+struct Limiter {
+    max_length: usize,
+    word_length: usize,
+    word_count: usize
+}
+
+impl Limiter {
+    fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            word_length: 0,
+            word_count: 0
+        }
+    }
+}
+
+impl Effector<Effect, State, LimitError> for Limiter {
+    fn dispatch(&mut self, effect: Effect, data: StreamData) -> Control<State, LimitError> {
+        match effect {
+            Effect::PushLetter => {
+                self.word_length += 1;
+
+                if self.word_length > self.max_length {
+                    return Control::Error(LimitError::TooLongWord { index: data.index });
+                }
+
+                Control::Continue
+            },
+            Effect::Separate => {
+                if self.word_length > 0 {
+                    self.word_count += 1;
+                    self.word_length = 0;
+                }
+
+                match data.character {
+                    Some('!') => Control::Stop,
+                    Some('#') => Control::GoTo(State::Comment),
+                    _ => Control::Continue
+                }
+            }
+        }
+    }
+}
+
+fn setup_fsm() -> FSM<State, Effect, LimitError> {
+    let fsm = FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::PushLetter)),
+                Transition::new(State::Space, None, Some(Effect::Separate))
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::PushLetter)),
+                Transition::new(State::Space, None, Some(Effect::Separate))
+            ],
+            State::Comment => vec![
+                Transition::new(State::Comment, None, None)
+            ]
+        ),
+        Some(Effect::Separate)
+    );
+
+    assert!(fsm.is_ok());
+
+    fsm.unwrap()
+}
+
+#[test]
+fn it_aborts_run_with_effect_error() {
+    let fsm = setup_fsm();
+
+    {
+        let string = String::from("ab abc");
+        let mut limiter = Limiter::new(3);
+
+        assert!(fsm.proceed(&string, Some(&mut limiter)).is_ok());
+        assert_eq!(limiter.word_count, 2);
+    }
+
+    {
+        let string = String::from("ab abcd ef");
+        let mut limiter = Limiter::new(3);
+
+        assert!(matches!(
+            fsm.proceed(&string, Some(&mut limiter)),
+            Err(FSMError::Effect(LimitError::TooLongWord { index: 6 }))
+        ));
+        assert_eq!(limiter.word_count, 1);
+    }
+}
+
+#[test]
+fn it_stops_and_redirects_run() {
+    let fsm = setup_fsm();
+
+    {
+        let string = String::from("ab cd! abcdef");
+        let mut limiter = Limiter::new(3);
+
+        assert!(fsm.proceed(&string, Some(&mut limiter)).is_ok());
+        assert_eq!(limiter.word_count, 2);
+    }
+
+    {
+        let string = String::from("ab #abcdef");
+        let mut limiter = Limiter::new(3);
+        let mut trace = Trace::new();
+
+        assert!(fsm.proceed_traced(&string, Some(&mut limiter), &mut trace).is_ok());
+        assert_eq!(limiter.word_count, 1);
+
+        assert_eq!(trace.steps()[3].to, State::Comment);
+        assert_eq!(trace.steps().last().map(|step| step.to), Some(State::Comment));
+        assert_eq!(trace.end_effects(), &vec![Effect::Separate]);
+    }
+}
+
+#[test]
+fn it_rejects_redirection_to_unknown_state() {
+    // "Comment" doesn't belong to FSM
+    let fsm: FSM<State, Effect, LimitError> = FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::PushLetter)),
+                Transition::new(State::Space, None, Some(Effect::Separate))
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::PushLetter)),
+                Transition::new(State::Space, None, Some(Effect::Separate))
+            ]
+        ),
+        Some(Effect::Separate)
+    ).unwrap();
+
+    let string = String::from("ab #cd");
+
+    {
+        let mut limiter = Limiter::new(3);
+
+        assert!(matches!(
+            fsm.proceed(&string, Some(&mut limiter)),
+            Err(FSMError::StateDoesNotExist(State::Comment))
+        ));
+    }
+
+    {
+        let mut limiter = Limiter::new(3);
+        let mut runner = Runner::start(&fsm, Some(&mut limiter)).unwrap();
+
+        assert!(runner.feed('a', Some(&mut limiter)).is_ok());
+        assert!(matches!(
+            runner.feed('#', Some(&mut limiter)),
+            Err(FSMError::StateDoesNotExist(State::Comment))
+        ));
+        assert!(matches!(runner.feed('c', Some(&mut limiter)), Err(FSMError::RunFailed)));
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn it_stops_and_redirects_parallel_run() {
+    let compiled = CompiledFSM::new(setup_fsm()).unwrap();
+
+    {
+        let string = "ab cd ".repeat(10) + "ef! abcdef";
+        let mut limiter = Limiter::new(3);
+
+        assert!(compiled.proceed_parallel(&string, 4, Some(&mut limiter)).is_ok());
+        assert_eq!(limiter.word_count, 21);
+    }
+
+    {
+        let string = "ab cd ".repeat(10) + &"#abcdef ".repeat(10);
+        let mut limiter = Limiter::new(3);
+
+        assert!(compiled.proceed_parallel(&string, 4, Some(&mut limiter)).is_ok());
+        assert_eq!(limiter.word_count, 20);
+    }
+
+    {
+        let string = "ab cd ".repeat(10) + "abcdef";
+        let mut limiter = Limiter::new(3);
+
+        assert!(matches!(
+            compiled.proceed_parallel(&string, 4, Some(&mut limiter)),
+            Err(FSMError::Effect(LimitError::TooLongWord { index: 63 }))
+        ));
+    }
+}
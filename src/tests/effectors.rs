@@ -0,0 +1,121 @@
+#![cfg(test)]
+
+use crate::types::{Control, StreamData};
+use crate::effectors::{HandlerEffector, FanOut};
+
+use super::utils::test_valid_string;
+use super::automatas::words_and_numbers::*;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    MarkWord,
+    AcceptWord,
+    Finish
+}
+
+#[derive(Default)]
+struct Words {
+    start: usize,
+    list: Vec<String>,
+    finished: bool
+}
+
+fn setup_splitter() -> HandlerEffector<Words, Effect, State> {
+    HandlerEffector::new(Words::default())
+        .on(Effect::MarkWord, |words, input_data| words.start = input_data.index)
+        .on(Effect::AcceptWord, |words, input_data| {
+            let word = input_data.string.chars()
+                .skip(words.start)
+                .take(input_data.index - words.start)
+                .collect();
+
+            words.list.push(word);
+        })
+        .on(Effect::Finish, |words, _| words.finished = true)
+}
+
+#[test]
+fn it_dispatches_effects_to_registered_handlers() {
+    let mut fsm = init_fsm(None, Some(Effect::Finish));
+
+    assert!(fsm.set_entry_effect(State::WORD, Some(Effect::MarkWord)).is_ok());
+    assert!(fsm.set_exit_effect(State::WORD, Some(Effect::AcceptWord)).is_ok());
+
+    let string = String::from("the quick, brown fox");
+    let mut splitter = setup_splitter();
+
+    test_valid_string(&fsm, &string, Some(&mut splitter));
+
+    let words = splitter.into_data();
+
+    assert_eq!(
+        words.list,
+        vec![
+            String::from("the"),
+            String::from("quick"),
+            String::from("brown"),
+            String::from("fox")
+        ]
+    );
+    assert!(words.finished);
+}
+
+#[test]
+fn it_uses_closures_and_fan_out() {
+    let mut fsm = init_fsm(None, Some(Effect::Finish));
+
+    assert!(fsm.set_entry_effect(State::WORD, Some(Effect::MarkWord)).is_ok());
+    assert!(fsm.set_exit_effect(State::WORD, Some(Effect::AcceptWord)).is_ok());
+
+    {
+        let string = String::from("one 2 three");
+        let mut journal = Vec::new();
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            journal.push((effect, input_data.index));
+        };
+
+        test_valid_string(&fsm, &string, Some(&mut logger));
+
+        assert_eq!(
+            journal,
+            vec![
+                (Effect::MarkWord, 0),
+                (Effect::AcceptWord, 3),
+                (Effect::MarkWord, 6),
+                (Effect::AcceptWord, 11),
+                (Effect::Finish, 11)
+            ]
+        );
+    }
+
+    {
+        let string = String::from("one two three");
+        let mut count = 0;
+        let mut counter = |effect: Effect, _: StreamData| {
+            if effect == Effect::AcceptWord {
+                count += 1;
+            }
+        };
+
+        let mut splitter = setup_splitter()
+            .on_control(Effect::AcceptWord, |words, _| {
+                if words.list.len() == 2 {
+                    Control::Stop
+                } else {
+                    Control::Continue
+                }
+            });
+
+        {
+            let mut fan_out = FanOut::new()
+                .with(&mut splitter)
+                .with(&mut counter);
+
+            test_valid_string(&fsm, &string, Some(&mut fan_out));
+        }
+
+        assert_eq!(splitter.data().list.len(), 2);
+        assert!(!splitter.data().finished);
+        assert_eq!(count, 1);
+    }
+}
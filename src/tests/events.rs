@@ -0,0 +1,141 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, StreamData};
+use crate::trace::Trace;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Closed,
+    Open,
+    Sending
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Event {
+    Connect,
+    Send,
+    Ack,
+    Close
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    Greet,
+    Transmit,
+    Hangup
+}
+
+fn is_connect(event: Event) -> bool {
+    event == Event::Connect
+}
+
+fn is_send(event: Event) -> bool {
+    event == Event::Send
+}
+
+fn is_ack(event: Event) -> bool {
+    event == Event::Ack
+}
+
+fn is_close(event: Event) -> bool {
+    event == Event::Close
+}
+
+fn setup_fsm() -> FSM<State, Effect, (), (), Event> {
+    let fsm = FSM::new(
+        State::Closed,
+        map!(
+            State::Closed => vec![
+                Transition::new(State::Open, Some(is_connect), Some(Effect::Greet))
+            ],
+            State::Open => vec![
+                Transition::new(State::Sending, Some(is_send), Some(Effect::Transmit)),
+                Transition::new(State::Closed, Some(is_close), Some(Effect::Hangup))
+            ],
+            State::Sending => vec![
+                Transition::new(State::Open, Some(is_ack), None)
+            ]
+        ),
+        None
+    );
+
+    assert!(fsm.is_ok());
+
+    fsm.unwrap()
+}
+
+#[test]
+fn it_runs_through_events() {
+    let fsm = setup_fsm();
+
+    {
+        let events = vec![Event::Connect, Event::Send, Event::Ack, Event::Send, Event::Ack, Event::Close];
+        let mut journal = Vec::new();
+        let mut logger = |effect: Effect, input_data: StreamData<Event>| {
+            journal.push((effect, input_data.index, input_data.character));
+        };
+
+        assert!(fsm.proceed_iter(events, Some(&mut logger)).is_ok());
+        assert_eq!(
+            journal,
+            vec![
+                (Effect::Greet, 0, Some(Event::Connect)),
+                (Effect::Transmit, 1, Some(Event::Send)),
+                (Effect::Transmit, 3, Some(Event::Send)),
+                (Effect::Hangup, 5, Some(Event::Close))
+            ]
+        );
+    }
+
+    {
+        let events = [Event::Connect, Event::Send, Event::Send];
+
+        assert!(matches!(
+            fsm.proceed_iter(events.iter().copied(), None),
+            Err(FSMError::NoValidTransition { from: State::Sending, input_data })
+                if input_data.index == 2 && input_data.character == Some(Event::Send)
+        ));
+    }
+
+    {
+        let mut trace = Trace::new();
+
+        assert!(fsm.proceed_iter_traced(vec![Event::Connect, Event::Close], None, &mut trace).is_ok());
+        assert_eq!(trace.steps().len(), 2);
+        assert_eq!(trace.steps()[1].character, Event::Close);
+        assert_eq!(trace.steps()[1].effects, vec![Effect::Hangup]);
+    }
+}
+
+#[test]
+fn it_runs_through_bytes() {
+    fn is_digit(byte: u8) -> bool {
+        byte.is_ascii_digit()
+    }
+
+    fn is_comma(byte: u8) -> bool {
+        byte == b','
+    }
+
+    let fsm: FSM<State, (), (), (), u8> = FSM::new(
+        State::Closed,
+        map!(
+            State::Closed => vec![
+                Transition::new(State::Open, Some(is_digit), None)
+            ],
+            State::Open => vec![
+                Transition::new(State::Open, Some(is_digit), None),
+                Transition::new(State::Closed, Some(is_comma), None)
+            ]
+        ),
+        None
+    ).unwrap();
+
+    assert!(fsm.proceed_iter(b"12,3,456".iter().copied(), None).is_ok());
+    assert!(matches!(
+        fsm.proceed_iter(b"12,,3".iter().copied(), None),
+        Err(FSMError::NoValidTransition { from: State::Closed, input_data })
+            if input_data.index == 3 && input_data.character == Some(b',')
+    ));
+}
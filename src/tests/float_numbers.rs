@@ -74,7 +74,7 @@ use std::hash::Hash;
                         State::INTEGER_PART,
                         Some(
                             |ch| {
-                                ('0'..='9').contains(&ch)
+                                ch.is_ascii_digit()
                             }
                         ),
                         None
@@ -94,7 +94,7 @@ use std::hash::Hash;
                         State::FRACTION_PART,
                         Some(
                             |ch| {
-                                ('0'..='9').contains(&ch)
+                                ch.is_ascii_digit()
                             }
                         ),
                         None
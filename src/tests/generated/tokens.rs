@@ -0,0 +1,112 @@
+/// Runs some string through FSM (see "FSM::proceed").
+/// Generated by fsm-rust-jb
+#[allow(unreachable_patterns, unused_mut)]
+pub fn run_tokens<'a>(
+    string: &'a String,
+    mut effector: Option<&'a mut dyn crate::types::Effector<Effect, State, ()>>
+) -> Result<(), crate::fsm::FSMError<'a, State, ()>> {
+    use crate::types::{Control, Effector, StreamData};
+    use crate::fsm::FSMError;
+
+    fn dispatch<'a, const N: usize>(
+        effector: &mut Option<&'a mut dyn Effector<Effect, State, ()>>,
+        effects: [Effect; N],
+        input_data: StreamData<'a>
+    ) -> Control<State, ()> {
+        if let Some(effector) = effector.as_mut() {
+            for effect in IntoIterator::into_iter(effects) {
+                match effector.dispatch(effect, input_data) {
+                    Control::Continue => {},
+                    control => return control
+                }
+            }
+        }
+
+        Control::Continue
+    }
+
+    fn resolve(state: State) -> State {
+        match state {
+            State::Token => State::Word,
+            state => state
+        }
+    }
+
+    let mut state = State::Init;
+    let control = dispatch(&mut effector, [], StreamData { string, index: 0, character: None });
+
+    match control {
+        Control::Continue => {},
+        Control::Stop => return Ok(()),
+        Control::Error(error) => return Err(FSMError::Effect(error)),
+        Control::GoTo(target) => state = resolve(target)
+    }
+
+    for (index, ch) in string.chars().enumerate() {
+        let input_data = StreamData { string, index, character: Some(ch) };
+
+        let control = match (state, ch) {
+            (State::Init, ch) if is_letter(ch) => {
+                state = State::Word;
+                dispatch(&mut effector, [Effect::Letter, Effect::EnterToken], input_data)
+            },
+            (State::Init, ch) if is_digit(ch) => {
+                state = State::Number;
+                dispatch(&mut effector, [Effect::Digit, Effect::EnterToken], input_data)
+            },
+            (State::Init, ' ') => {
+                state = State::Init;
+                dispatch(&mut effector, [], input_data)
+            },
+            (State::Init, '#') => {
+                state = State::Word;
+                dispatch(&mut effector, [Effect::EnterToken], input_data)
+            },
+            (State::Init, _) => return Err(FSMError::NoValidTransition { from: state, input_data }),
+            (State::Token, ' ') => {
+                state = State::Init;
+                dispatch(&mut effector, [Effect::ExitToken], input_data)
+            },
+            (State::Token, _) => return Err(FSMError::NoValidTransition { from: state, input_data }),
+            (State::Word, ch) if is_letter(ch) => {
+                state = State::Word;
+                dispatch(&mut effector, [Effect::Letter], input_data)
+            },
+            (State::Word, ' ') => {
+                state = State::Init;
+                dispatch(&mut effector, [Effect::ExitToken], input_data)
+            },
+            (State::Word, _) => return Err(FSMError::NoValidTransition { from: state, input_data }),
+            (State::Number, ch) if is_digit(ch) => {
+                state = State::Number;
+                dispatch(&mut effector, [Effect::Digit], input_data)
+            },
+            (State::Number, ' ') => {
+                state = State::Init;
+                dispatch(&mut effector, [Effect::ExitToken], input_data)
+            },
+            (State::Number, _) => return Err(FSMError::NoValidTransition { from: state, input_data }),
+            (state, _) => return Err(FSMError::StateDoesNotExist(state))
+        };
+
+        match control {
+            Control::Continue => {},
+            Control::Stop => return Ok(()),
+            Control::Error(error) => return Err(FSMError::Effect(error)),
+            Control::GoTo(target) => state = resolve(target)
+        }
+    }
+
+    let end_data = StreamData { string, index: string.len(), character: None };
+    let control = match state {
+        State::Token => dispatch(&mut effector, [Effect::ExitToken, Effect::Done], end_data),
+        State::Word => dispatch(&mut effector, [Effect::ExitToken, Effect::Done], end_data),
+        State::Number => dispatch(&mut effector, [Effect::ExitToken, Effect::Done], end_data),
+        _ => dispatch(&mut effector, [Effect::Done], end_data)
+    };
+
+    match control {
+        Control::Error(error) => Err(FSMError::Effect(error)),
+        _ => Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::Transition;
+use crate::effectors::HandlerEffector;
+use crate::runner::Runner;
+use crate::scxml::{ScxmlVocabulary, ScxmlError, to_scxml};
+
+use super::utils::{is_digit, is_dot};
+
+const MAX_DIGITS: usize = 8;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Number
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    PushDigit,
+    PushDot
+}
+
+#[derive(Default)]
+struct Number {
+    digits: usize,
+    has_dot: bool
+}
+
+type Context = HandlerEffector<Number, Effect, State>;
+
+fn has_room(_ch: char, context: &Context) -> bool {
+    context.data().digits < MAX_DIGITS
+}
+
+fn has_no_dot(_ch: char, context: &Context) -> bool {
+    !context.data().has_dot
+}
+
+fn setup_fsm() -> FSM<State, Effect, (), Context> {
+    let fsm = FSM::new(
+        State::Number,
+        map!(
+            State::Number => vec![
+                Transition::guarded(
+                    State::Number,
+                    Some(is_digit),
+                    has_room,
+                    vec![Effect::PushDigit]
+                ),
+                Transition::guarded(
+                    State::Number,
+                    Some(is_dot),
+                    has_no_dot,
+                    vec![Effect::PushDot]
+                )
+            ]
+        ),
+        None
+    );
+
+    assert!(fsm.is_ok());
+
+    fsm.unwrap()
+}
+
+fn setup_context() -> Context {
+    HandlerEffector::new(Number::default())
+        .on(Effect::PushDigit, |number, _| number.digits += 1)
+        .on(Effect::PushDot, |number, _| number.has_dot = true)
+}
+
+#[test]
+fn it_validates_transitions_by_context() {
+    let fsm = setup_fsm();
+
+    {
+        let string = String::from("1234.5678");
+        let mut context = setup_context();
+
+        assert!(fsm.proceed_in_context(&string, &mut context).is_ok());
+        assert_eq!(context.data().digits, 8);
+        assert!(context.data().has_dot);
+    }
+
+    {
+        let string = String::from("12.34.5");
+        let mut context = setup_context();
+
+        assert!(matches!(
+            fsm.proceed_in_context(&string, &mut context),
+            Err(FSMError::NoValidTransition { from: State::Number, input_data })
+                if input_data.index == 5
+        ));
+    }
+
+    {
+        let string = String::from("123456789");
+        let mut context = setup_context();
+
+        assert!(matches!(
+            fsm.proceed_in_context(&string, &mut context),
+            Err(FSMError::NoValidTransition { from: State::Number, input_data })
+                if input_data.index == 8
+        ));
+    }
+
+    {
+        let string = String::from("1");
+
+        assert!(matches!(
+            fsm.proceed(&string, None),
+            Err(FSMError::NoValidTransition { from: State::Number, .. })
+        ));
+    }
+}
+
+#[test]
+fn it_validates_fed_items_by_context() {
+    let fsm = setup_fsm();
+    let mut context = setup_context();
+    let mut runner = Runner::start(&fsm, Some(&mut context)).unwrap();
+
+    for ch in "12.3".chars() {
+        assert!(runner.feed_in_context(ch, &mut context).is_ok());
+    }
+
+    assert!(matches!(
+        runner.feed_in_context('.', &mut context),
+        Err(FSMError::NoValidTransition { from: State::Number, input_data })
+            if input_data.index == 4
+    ));
+    assert_eq!(context.data().digits, 3);
+
+    // guards don't match without context
+    let mut runner = Runner::start(&fsm, None).unwrap();
+
+    assert!(matches!(
+        runner.feed('1', None),
+        Err(FSMError::NoValidTransition { from: State::Number, .. })
+    ));
+}
+
+#[test]
+fn it_does_not_export_guarded_transitions() {
+    let fsm = setup_fsm();
+    let vocabulary = ScxmlVocabulary::new()
+        .state(State::Number, "number")
+        .condition("is_digit", is_digit)
+        .condition("is_dot", is_dot)
+        .effect(Effect::PushDigit, "push_digit")
+        .effect(Effect::PushDot, "push_dot");
+
+    assert!(matches!(
+        to_scxml(&fsm, &vocabulary),
+        Err(ScxmlError::GuardedTransition { from: State::Number, index: 0 })
+    ));
+}
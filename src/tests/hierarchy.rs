@@ -0,0 +1,308 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, Effector, Control, StreamData, Observer};
+use crate::hierarchy::History;
+use crate::trace::Trace;
+use crate::runner::Runner;
+
+use super::utils::{is_letter, is_digit};
+
+mod tokens {
+    use super::*;
+
+    #[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+    pub enum State {
+        Init,
+        Token,
+        Word,
+        Number
+    }
+
+    #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+    pub enum Effect {
+        EnterToken,
+        ExitToken,
+        EnterWord,
+        ExitWord
+    }
+
+    /// Escape transition of tokens is declared once (in composite state)
+    pub fn setup_fsm() -> FSM<State, Effect> {
+        let mut fsm = FSM::new(
+            State::Init,
+            map!(
+                State::Init => vec![
+                    Transition::new(State::Word, Some(is_letter), None),
+                    Transition::new(State::Number, Some(is_digit), None),
+                    Transition::new(State::Init, None, None)
+                ],
+                State::Token => vec![
+                    Transition::new(State::Init, None, None)
+                ],
+                State::Word => vec![
+                    Transition::new(State::Word, Some(is_letter), None)
+                ],
+                State::Number => vec![
+                    Transition::new(State::Number, Some(is_digit), None)
+                ]
+            ),
+            None
+        ).unwrap();
+
+        assert!(fsm.set_parent(State::Word, State::Token).is_ok());
+        assert!(fsm.set_parent(State::Number, State::Token).is_ok());
+
+        assert!(fsm.set_entry_effect(State::Token, Some(Effect::EnterToken)).is_ok());
+        assert!(fsm.set_exit_effect(State::Token, Some(Effect::ExitToken)).is_ok());
+        assert!(fsm.set_entry_effect(State::Word, Some(Effect::EnterWord)).is_ok());
+        assert!(fsm.set_exit_effect(State::Word, Some(Effect::ExitWord)).is_ok());
+
+        fsm
+    }
+}
+
+mod player {
+    use super::*;
+
+    #[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+    pub enum State {
+        Off,
+        On,
+        Radio,
+        Cd,
+        Track1,
+        Track2,
+        ShallowHistory,
+        DeepHistory
+    }
+
+    fn is_power(ch: char) -> bool {
+        ch == 'p'
+    }
+
+    fn is_resume(ch: char) -> bool {
+        ch == 'r'
+    }
+
+    fn is_mode(ch: char) -> bool {
+        ch == 'm'
+    }
+
+    fn is_next(ch: char) -> bool {
+        ch == 'n'
+    }
+
+    pub fn setup_fsm() -> FSM<State, ()> {
+        let mut fsm = FSM::new(
+            State::Off,
+            map!(
+                State::Off => vec![
+                    Transition::new(State::ShallowHistory, Some(is_power), None),
+                    Transition::new(State::DeepHistory, Some(is_resume), None)
+                ],
+                State::On => vec![
+                    Transition::new(State::Off, Some(is_power), None)
+                ],
+                State::Radio => vec![
+                    Transition::new(State::Cd, Some(is_mode), None)
+                ],
+                State::Cd => vec![
+                    Transition::new(State::Radio, Some(is_mode), None)
+                ],
+                State::Track1 => vec![
+                    Transition::new(State::Track2, Some(is_next), None)
+                ],
+                State::Track2 => vec![
+                    Transition::new(State::Track1, Some(is_next), None)
+                ]
+            ),
+            None
+        ).unwrap();
+
+        assert!(fsm.set_parent(State::Radio, State::On).is_ok());
+        assert!(fsm.set_parent(State::Cd, State::On).is_ok());
+        assert!(fsm.set_parent(State::Track1, State::Cd).is_ok());
+        assert!(fsm.set_parent(State::Track2, State::Cd).is_ok());
+        assert!(fsm.set_initial_child(State::On, State::Radio).is_ok());
+        assert!(fsm.set_initial_child(State::Cd, State::Track1).is_ok());
+        assert!(fsm.add_history(State::ShallowHistory, State::On, History::Shallow).is_ok());
+        assert!(fsm.add_history(State::DeepHistory, State::On, History::Deep).is_ok());
+
+        fsm
+    }
+
+    pub fn last_state(fsm: &FSM<State, ()>, string: &str) -> Option<State> {
+        let string = String::from(string);
+        let mut trace = Trace::new();
+
+        assert!(fsm.proceed_traced(&string, None, &mut trace).is_ok());
+
+        trace.steps().last().map(|step| step.to)
+    }
+}
+
+#[test]
+fn it_inherits_transitions_of_composite_states() {
+    use tokens::*;
+
+    let fsm = setup_fsm();
+    let string = String::from("ab 12");
+    let mut journal = Vec::new();
+    let mut logger = |effect: Effect, input_data: StreamData| {
+        journal.push((effect, input_data.index));
+    };
+
+    assert!(fsm.proceed(&string, Some(&mut logger)).is_ok());
+    assert_eq!(
+        journal,
+        vec![
+            (Effect::EnterToken, 0),
+            (Effect::EnterWord, 0),
+            (Effect::ExitWord, 2),
+            (Effect::ExitToken, 2),
+            (Effect::EnterToken, 3),
+            (Effect::ExitToken, 5)
+        ]
+    );
+
+    let mut trace = Trace::new();
+
+    assert!(fsm.proceed_traced(&string, None, &mut trace).is_ok());
+    assert_eq!(trace.steps()[2].from, State::Word);
+    assert_eq!(trace.steps()[2].transition, 1);
+    assert_eq!(trace.steps()[2].to, State::Init);
+    assert_eq!(trace.end_effects(), &vec![Effect::ExitToken]);
+}
+
+#[test]
+fn it_resumes_history() {
+    use player::*;
+
+    let fsm = setup_fsm();
+
+    assert_eq!(last_state(&fsm, "p"), Some(State::Radio));
+    assert_eq!(last_state(&fsm, "pm"), Some(State::Track1));
+    assert_eq!(last_state(&fsm, "pmnpp"), Some(State::Track1));
+    assert_eq!(last_state(&fsm, "pmnpr"), Some(State::Track2));
+    assert_eq!(last_state(&fsm, "r"), Some(State::Radio));
+    assert_eq!(last_state(&fsm, "pmnprpr"), Some(State::Track2));
+}
+
+#[test]
+fn it_resolves_redirection_after_last_item() {
+    use player::*;
+
+    /// Redirects run by every effect
+    struct Redirect(State);
+
+    impl Effector<(), State> for Redirect {
+        fn dispatch(&mut self, _effect: (), _data: StreamData) -> Control<State> {
+            Control::GoTo(self.0)
+        }
+    }
+
+    let mut fsm = setup_fsm();
+    fsm.push_post_effect(());
+
+    let last_state = |string: &str, target: State| {
+        let mut runner = Runner::start(&fsm, None).unwrap();
+
+        for ch in string.chars() {
+            assert!(runner.feed(ch, None).is_ok());
+        }
+
+        runner.finish(Some(&mut Redirect(target))).unwrap()
+    };
+
+    assert_eq!(last_state("pmnp", State::On), State::Radio);
+    assert_eq!(last_state("pmnp", State::DeepHistory), State::Track2);
+    assert_eq!(last_state("pmn", State::Off), State::Off);
+}
+
+#[test]
+fn it_notifies_about_states_of_redirection() {
+    use tokens::*;
+
+    /// Redirects run to number on leaving token
+    struct Redirect;
+
+    impl Effector<Effect, State> for Redirect {
+        fn dispatch(&mut self, effect: Effect, _data: StreamData) -> Control<State> {
+            match effect {
+                Effect::ExitToken => Control::GoTo(State::Number),
+                _ => Control::Continue
+            }
+        }
+    }
+
+    /// Logs entered (true) and left (false) states
+    #[derive(Default)]
+    struct Logger(Vec<(bool, State)>);
+
+    impl Observer<State> for Logger {
+        fn on_enter(&mut self, state: State) {
+            self.0.push((true, state));
+        }
+
+        fn on_exit(&mut self, state: State) {
+            self.0.push((false, state));
+        }
+    }
+
+    let fsm = setup_fsm();
+    let string = String::from("a1");
+    let mut logger = Logger::default();
+
+    assert!(fsm.proceed_observed(&string, Some(&mut Redirect), &mut logger).is_ok());
+    assert_eq!(
+        logger.0,
+        vec![
+            (true, State::Init),
+            (false, State::Init),
+            (true, State::Token),
+            (true, State::Word),
+            (false, State::Word),
+            (false, State::Token),
+            (true, State::Token),
+            (true, State::Number)
+        ]
+    );
+}
+
+#[test]
+fn it_drops_initial_child_of_former_composite_state() {
+    use tokens::*;
+
+    let mut fsm = setup_fsm();
+
+    assert!(fsm.set_initial_child(State::Token, State::Word).is_ok());
+    assert!(fsm.set_parent(State::Word, State::Init).is_ok());
+    assert!(fsm.set_parent(State::Token, State::Word).is_ok());
+    assert!(fsm.set_initial_child(State::Word, State::Token).is_ok());
+
+    let mut runner = Runner::start(&fsm, None).unwrap();
+
+    assert!(runner.feed('a', None).is_ok());
+    assert_eq!(runner.state(), State::Token);
+}
+
+#[test]
+fn it_rejects_invalid_nesting() {
+    use player::*;
+
+    let mut fsm = setup_fsm();
+
+    assert!(matches!(
+        fsm.set_parent(State::On, State::Track1),
+        Err(FSMError::CyclicHierarchy(State::On))
+    ));
+    assert!(matches!(
+        fsm.set_initial_child(State::On, State::Track1),
+        Err(FSMError::NotChild { parent: State::On, child: State::Track1 })
+    ));
+    assert!(matches!(
+        fsm.set_parent(State::ShallowHistory, State::On),
+        Err(FSMError::StateDoesNotExist(State::ShallowHistory))
+    ));
+}
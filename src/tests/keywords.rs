@@ -0,0 +1,81 @@
+#![cfg(test)]
+
+use crate::fsm::FSM;
+use crate::types::StreamData;
+use crate::keywords::Keywords;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Keyword {
+    He,
+    She,
+    His,
+    Hers
+}
+
+fn setup_keywords(is_case_insensitive: bool, is_overlapping: bool) -> FSM<usize, Keyword> {
+    Keywords::new()
+        .pattern("he", Keyword::He)
+        .pattern("she", Keyword::She)
+        .pattern("his", Keyword::His)
+        .pattern("hers", Keyword::Hers)
+        .case_insensitive(is_case_insensitive)
+        .overlapping(is_overlapping)
+        .build()
+        .unwrap()
+}
+
+fn detect(fsm: &FSM<usize, Keyword>, text: &str) -> Vec<(Keyword, usize)> {
+    let string = String::from(text);
+    let mut journal = Vec::new();
+    let mut logger = |keyword: Keyword, input_data: StreamData| {
+        journal.push((keyword, input_data.index));
+    };
+
+    assert!(fsm.proceed(&string, Some(&mut logger)).is_ok());
+
+    journal
+}
+
+#[test]
+fn it_reports_overlapping_keywords() {
+    let fsm = setup_keywords(false, true);
+
+    assert_eq!(
+        detect(&fsm, "ushers or his"),
+        vec![
+            (Keyword::She, 3),
+            (Keyword::He, 3),
+            (Keyword::Hers, 5),
+            (Keyword::His, 12)
+        ]
+    );
+    assert_eq!(detect(&fsm, "USHERS"), vec![]);
+}
+
+#[test]
+fn it_reports_non_overlapping_keywords() {
+    let fsm = setup_keywords(false, false);
+
+    assert_eq!(
+        detect(&fsm, "ushers hehe"),
+        vec![
+            (Keyword::She, 3),
+            (Keyword::He, 8),
+            (Keyword::He, 10)
+        ]
+    );
+}
+
+#[test]
+fn it_ignores_case() {
+    let fsm = setup_keywords(true, true);
+
+    assert_eq!(
+        detect(&fsm, "uSHeRs"),
+        vec![
+            (Keyword::She, 3),
+            (Keyword::He, 3),
+            (Keyword::Hers, 5)
+        ]
+    );
+}
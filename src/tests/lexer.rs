@@ -0,0 +1,120 @@
+#![cfg(test)]
+
+use crate::fsm::FSM;
+use crate::types::Transition;
+use crate::lexer::Lexer;
+
+use super::utils::{is_letter, is_digit, is_dot};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Start,
+    Ident,
+    Integer,
+    Point,
+    Fraction,
+    Dot,
+    Range,
+    Space
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Kind {
+    Ident,
+    Number,
+    Dot,
+    Range,
+    Space
+}
+
+fn is_space(ch: char) -> bool {
+    ch == ' '
+}
+
+fn setup_lexer() -> Lexer<State, Kind> {
+    let fsm = FSM::new(
+        State::Start,
+        map!(
+            State::Start => vec![
+                Transition::new(State::Ident, Some(is_letter), None),
+                Transition::new(State::Integer, Some(is_digit), None),
+                Transition::new(State::Dot, Some(is_dot), None),
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Ident => vec![
+                Transition::new(State::Ident, Some(is_letter), None),
+                Transition::new(State::Ident, Some(is_digit), None)
+            ],
+            State::Integer => vec![
+                Transition::new(State::Integer, Some(is_digit), None),
+                Transition::new(State::Point, Some(is_dot), None)
+            ],
+            State::Point => vec![
+                Transition::new(State::Fraction, Some(is_digit), None)
+            ],
+            State::Fraction => vec![
+                Transition::new(State::Fraction, Some(is_digit), None)
+            ],
+            State::Dot => vec![
+                Transition::new(State::Range, Some(is_dot), None)
+            ],
+            State::Range => vec![],
+            State::Space => vec![
+                Transition::new(State::Space, Some(is_space), None)
+            ]
+        ),
+        None
+    ).unwrap();
+
+    Lexer::new(
+        fsm,
+        map!(
+            State::Ident => Kind::Ident,
+            State::Integer => Kind::Number,
+            State::Fraction => Kind::Number,
+            State::Dot => Kind::Dot,
+            State::Range => Kind::Range,
+            State::Space => Kind::Space
+        )
+    ).unwrap()
+}
+
+#[test]
+fn it_splits_input_into_longest_tokens() {
+    let lexer = setup_lexer();
+
+    let tokens: Vec<(Option<Kind>, &str)> = lexer.tokens("x1 3.14 1..2 a.b")
+        .map(|token| (token.kind, token.text))
+        .collect();
+
+    assert_eq!(
+        tokens,
+        vec![
+            (Some(Kind::Ident), "x1"),
+            (Some(Kind::Space), " "),
+            (Some(Kind::Number), "3.14"),
+            (Some(Kind::Space), " "),
+            (Some(Kind::Number), "1"),
+            (Some(Kind::Range), ".."),
+            (Some(Kind::Number), "2"),
+            (Some(Kind::Space), " "),
+            (Some(Kind::Ident), "a"),
+            (Some(Kind::Dot), "."),
+            (Some(Kind::Ident), "b")
+        ]
+    );
+}
+
+#[test]
+fn it_yields_error_tokens_for_unmatched_input() {
+    let lexer = setup_lexer();
+
+    let tokens: Vec<_> = lexer.tokens("ab ?!é 7").collect();
+
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(tokens[2].kind, None);
+    assert_eq!(tokens[2].text, "?!é");
+    assert_eq!(tokens[2].span, 3..7);
+    assert_eq!(tokens[4].kind, Some(Kind::Number));
+    assert_eq!(tokens[4].span, 8..9);
+}
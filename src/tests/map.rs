@@ -0,0 +1,41 @@
+#![cfg(test)]
+
+use crate::map::{Map, Set};
+
+#[test]
+fn it_maps_keys_to_values() {
+    let mut map: Map<char, usize> = map!('a' => 1, 'b' => 2);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.insert('a', 3), Some(1));
+    assert_eq!(map.insert('c', 4), None);
+    assert_eq!(map[&'a'], 3);
+    assert!(map.contains_key(&'c'));
+
+    *map.entry('d').or_insert_with(|| 0) += 5;
+    *map.entry('d').or_insert_with(|| 0) += 5;
+    assert_eq!(map.get(&'d'), Some(&10));
+
+    if let Some(value) = map.get_mut(&'b') {
+        *value = 7;
+    }
+
+    assert_eq!(map.remove(&'c'), Some(4));
+    assert_eq!(map.get(&'c'), None);
+
+    let mut entries: Vec<(char, usize)> = map.into_iter().collect();
+    entries.sort_unstable();
+
+    assert_eq!(entries, vec![('a', 3), ('b', 7), ('d', 10)]);
+}
+
+#[test]
+fn it_keeps_unique_items() {
+    let mut set: Set<usize> = vec![1, 2, 1].into_iter().collect();
+
+    assert_eq!(set.len(), 2);
+    assert!(!set.insert(2));
+    assert!(set.insert(3));
+    assert!(set.contains(&3));
+    assert!(!set.contains(&4));
+}
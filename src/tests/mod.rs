@@ -1,5 +1,30 @@
+#![allow(non_camel_case_types, clippy::upper_case_acronyms)]
+
 mod utils;
 mod automatas;
 mod float_numbers;
 mod count_words_and_numbers;
 mod split_into_words_and_count_sum;
+mod scxml;
+mod trace;
+mod observer;
+mod state_effects;
+mod effect_control;
+mod effectors;
+mod owned_effects;
+mod guards;
+mod registers;
+mod pda;
+mod events;
+mod hierarchy;
+mod parallel;
+mod lexer;
+mod search;
+mod keywords;
+mod compiled;
+mod codegen;
+mod byte_dfa;
+mod runner;
+mod map;
+mod static_fsm;
+mod stream;
@@ -0,0 +1,107 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+use crate::fsm::FSM;
+use crate::types::{Transition, Observer, StreamData};
+
+use super::utils::{is_digit, is_letter};
+use super::automatas::words_and_numbers::*;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Event {
+    Enter(State),
+    Exit(State),
+    Transition(State, State, usize),
+    Reject(State, usize),
+    Finish(State)
+}
+
+#[derive(Default)]
+struct Logger {
+    events: Vec<Event>,
+    visits: HashMap<State, usize>
+}
+
+impl Observer<State> for Logger {
+    fn on_enter(&mut self, state: State) {
+        self.events.push(Event::Enter(state));
+        *self.visits.entry(state).or_insert(0) += 1;
+    }
+
+    fn on_exit(&mut self, state: State) {
+        self.events.push(Event::Exit(state));
+    }
+
+    fn on_transition(&mut self, from: State, to: State, input_data: &StreamData) {
+        self.events.push(Event::Transition(from, to, input_data.index));
+    }
+
+    fn on_reject(&mut self, state: State, input_data: &StreamData) {
+        self.events.push(Event::Reject(state, input_data.index));
+    }
+
+    fn on_finish(&mut self, state: State) {
+        self.events.push(Event::Finish(state));
+    }
+}
+
+#[test]
+fn it_notifies_about_every_step() {
+    let fsm = init_fsm::<u8>(None, None);
+    let string = String::from("a1");
+    let mut logger = Logger::default();
+
+    assert!(fsm.proceed_observed(&string, None, &mut logger).is_ok());
+
+    assert_eq!(
+        logger.events,
+        vec![
+            Event::Enter(State::INIT),
+            Event::Exit(State::INIT),
+            Event::Transition(State::INIT, State::WORD, 0),
+            Event::Enter(State::WORD),
+            Event::Exit(State::WORD),
+            Event::Transition(State::WORD, State::NUMBER_IP, 1),
+            Event::Enter(State::NUMBER_IP),
+            Event::Finish(State::NUMBER_IP)
+        ]
+    );
+
+    let string = String::from("the quick brown fox");
+    let mut logger = Logger::default();
+
+    assert!(fsm.proceed_observed(&string, None, &mut logger).is_ok());
+
+    assert_eq!(logger.visits.get(&State::INIT), Some(&4));
+    assert_eq!(logger.visits.get(&State::WORD), Some(&16));
+    assert_eq!(logger.visits.get(&State::NUMBER_IP), None);
+}
+
+#[test]
+fn it_notifies_about_rejection() {
+    let fsm: FSM<State, u8> = FSM::new(
+        State::INIT,
+        map!(
+            State::INIT => vec![
+                Transition::new(State::WORD, Some(is_letter), None)
+            ],
+            State::WORD => vec![
+                Transition::new(State::WORD, Some(is_letter), None),
+                Transition::new(State::NUMBER_IP, Some(is_digit), None)
+            ],
+            State::NUMBER_IP => vec![]
+        ),
+        None
+    ).unwrap();
+
+    let string = String::from("ab1c");
+    let mut logger = Logger::default();
+
+    assert!(fsm.proceed_observed(&string, None, &mut logger).is_err());
+
+    assert_eq!(
+        logger.events.last(),
+        Some(&Event::Reject(State::NUMBER_IP, 3))
+    );
+    assert!(!logger.events.iter().any(|event| matches!(event, Event::Finish(_))));
+}
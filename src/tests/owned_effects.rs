@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+use std::sync::Arc;
+use crate::fsm::FSM;
+use crate::types::{Transition, Effector, Control, StreamData};
+use crate::trace::Trace;
+
+use super::utils::{test_valid_string, is_digit, is_letter};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Other,
+    Letter,
+    Digit
+}
+
+/// Effect that owns its configuration
+#[derive(Clone)]
+enum Effect {
+    Label(String),
+    Format(Arc<dyn Fn(char) -> String>)
+}
+
+#[derive(Default)]
+struct Output {
+    lines: Vec<String>
+}
+
+impl Effector<Effect, State> for Output {
+    fn dispatch(&mut self, effect: Effect, input_data: StreamData) -> Control<State> {
+        let line = match effect {
+            Effect::Label(label) => match input_data.character {
+                Some(ch) => format!("{} {}", label, ch),
+                None => label
+            },
+            Effect::Format(format) => format(input_data.character.unwrap())
+        };
+
+        self.lines.push(line);
+
+        Control::Continue
+    }
+}
+
+#[test]
+fn it_dispatches_data_carrying_effects() {
+    let format: Arc<dyn Fn(char) -> String> = Arc::new(|ch| format!("<{}>", ch));
+
+    let fsm: FSM<State, Effect> = FSM::new(
+        State::Other,
+        map!(
+            State::Other => vec![
+                Transition::new(
+                    State::Letter,
+                    Some(is_letter),
+                    Some(Effect::Label(String::from("letter")))
+                ),
+                Transition::new(
+                    State::Digit,
+                    Some(is_digit),
+                    Some(Effect::Format(Arc::clone(&format)))
+                ),
+                Transition::new(State::Other, None, None)
+            ],
+            State::Letter => vec![
+                Transition::new(State::Letter, Some(is_letter), None),
+                Transition::new(State::Other, None, None)
+            ],
+            State::Digit => vec![
+                Transition::new(State::Digit, Some(is_digit), None),
+                Transition::new(State::Other, None, None)
+            ]
+        ),
+        Some(Effect::Label(String::from("end")))
+    ).unwrap();
+
+    let string = String::from("ab 12 c");
+
+    {
+        let mut output = Output::default();
+
+        test_valid_string(&fsm, &string, Some(&mut output));
+
+        assert_eq!(
+            output.lines,
+            vec![
+                String::from("letter a"),
+                String::from("<1>"),
+                String::from("letter c"),
+                String::from("end")
+            ]
+        );
+    }
+
+    {
+        let mut trace = Trace::new();
+
+        assert!(fsm.proceed_traced(&string, None, &mut trace).is_ok());
+
+        let mut output = Output::default();
+        assert!(trace.replay(&mut output).is_ok());
+
+        assert_eq!(output.lines.len(), 4);
+        assert_eq!(Arc::strong_count(&format), 3);
+    }
+
+    assert_eq!(Arc::strong_count(&format), 2);
+}
@@ -0,0 +1,139 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, StreamData};
+use crate::parallel::Parallel;
+
+use super::utils::is_letter;
+
+/// States of both regions
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Space,
+    Word,
+    Outside,
+    Inside
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    StartWord,
+    Open,
+    Close
+}
+
+fn is_open(ch: char) -> bool {
+    ch == '('
+}
+
+fn is_close(ch: char) -> bool {
+    ch == ')'
+}
+
+fn is_not_bracket(ch: char) -> bool {
+    !is_open(ch) && !is_close(ch)
+}
+
+fn setup_words() -> FSM<State, Effect> {
+    FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::StartWord)),
+                Transition::new(State::Space, None, None)
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), None),
+                Transition::new(State::Space, None, None)
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+/// Tracks brackets that can't be nested
+fn setup_brackets() -> FSM<State, Effect> {
+    FSM::new(
+        State::Outside,
+        map!(
+            State::Outside => vec![
+                Transition::new(State::Inside, Some(is_open), Some(Effect::Open)),
+                Transition::new(State::Outside, Some(is_not_bracket), None)
+            ],
+            State::Inside => vec![
+                Transition::new(State::Outside, Some(is_close), Some(Effect::Close)),
+                Transition::new(State::Inside, Some(is_not_bracket), None)
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+#[test]
+fn it_runs_regions_in_lockstep() {
+    let words = setup_words();
+    let brackets = setup_brackets();
+    let parallel = Parallel::new()
+        .with(&words)
+        .with(&brackets);
+
+    assert_eq!(parallel.len(), 2);
+
+    {
+        let string = String::from("one (two three) four");
+        let mut word_count = 0;
+        let mut counter = |effect: Effect, _: StreamData| {
+            if effect == Effect::StartWord {
+                word_count += 1;
+            }
+        };
+        let mut spans = Vec::new();
+        let mut tracker = |effect: Effect, input_data: StreamData| {
+            spans.push((effect, input_data.index));
+        };
+
+        assert!(parallel.proceed(&string, vec![Some(&mut counter), Some(&mut tracker)]).is_ok());
+        assert_eq!(word_count, 4);
+        assert_eq!(spans, vec![(Effect::Open, 4), (Effect::Close, 14)]);
+    }
+
+    {
+        let string = String::from("a(b)");
+        let mut journal = Vec::new();
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            journal.push((effect, input_data.index));
+        };
+
+        assert!(parallel.proceed_shared(&string, Some(&mut logger)).is_ok());
+        assert_eq!(
+            journal,
+            vec![
+                (Effect::StartWord, 0),
+                (Effect::Open, 1),
+                (Effect::StartWord, 2),
+                (Effect::Close, 3)
+            ]
+        );
+    }
+}
+
+#[test]
+fn it_reports_rejecting_region() {
+    let words = setup_words();
+    let brackets = setup_brackets();
+    let parallel = Parallel::new()
+        .with(&words)
+        .with(&brackets);
+
+    let string = String::from("one (two (three))");
+
+    let result = parallel.proceed(&string, vec![]);
+
+    assert!(matches!(
+        result,
+        Err(ref error) if error.region == 1 && matches!(
+            error.error,
+            FSMError::NoValidTransition { from: State::Inside, input_data } if input_data.index == 9
+        )
+    ));
+}
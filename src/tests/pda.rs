@@ -0,0 +1,281 @@
+#![cfg(test)]
+
+use crate::fsm::FSMError;
+use crate::pda::{PDA, PDATransition, StackOp};
+use crate::types::{Transition, Effector, Control, StreamData};
+use crate::registers::{RegisterTest, RegisterUpdate};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Code,
+    Quoted
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Bracket {
+    Round,
+    Square
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    Open,
+    Close,
+    Finish
+}
+
+fn is_round_open(ch: char) -> bool {
+    ch == '('
+}
+
+fn is_round_close(ch: char) -> bool {
+    ch == ')'
+}
+
+fn is_square_open(ch: char) -> bool {
+    ch == '['
+}
+
+fn is_square_close(ch: char) -> bool {
+    ch == ']'
+}
+
+fn is_quote(ch: char) -> bool {
+    ch == '"'
+}
+
+fn is_bracket(ch: char) -> bool {
+    is_round_open(ch) || is_round_close(ch) || is_square_open(ch) || is_square_close(ch)
+}
+
+fn is_not_bracket(ch: char) -> bool {
+    !is_bracket(ch)
+}
+
+/// Validates balanced brackets (brackets inside quotes are ignored)
+fn setup_pda() -> PDA<State, Bracket, Effect> {
+    let pda = PDA::new(
+        State::Code,
+        map!(
+            State::Code => vec![
+                PDATransition::new(
+                    State::Code, Some(is_round_open), None,
+                    StackOp::Push(Bracket::Round), vec![Effect::Open]
+                ),
+                PDATransition::new(
+                    State::Code, Some(is_square_open), None,
+                    StackOp::Push(Bracket::Square), vec![Effect::Open]
+                ),
+                PDATransition::new(
+                    State::Code, Some(is_round_close), Some(Bracket::Round),
+                    StackOp::Pop, vec![Effect::Close]
+                ),
+                PDATransition::new(
+                    State::Code, Some(is_square_close), Some(Bracket::Square),
+                    StackOp::Pop, vec![Effect::Close]
+                ),
+                PDATransition::new(
+                    State::Quoted, Some(is_quote), None,
+                    StackOp::Keep, vec![]
+                ),
+                PDATransition::new(
+                    State::Code, Some(is_not_bracket), None,
+                    StackOp::Keep, vec![]
+                )
+            ],
+            State::Quoted => vec![
+                PDATransition::new(
+                    State::Code, Some(is_quote), None,
+                    StackOp::Keep, vec![]
+                ),
+                PDATransition::new(
+                    State::Quoted, None, None,
+                    StackOp::Keep, vec![]
+                )
+            ]
+        ),
+        vec![Effect::Finish]
+    );
+
+    assert!(pda.is_ok());
+
+    pda.unwrap()
+}
+
+#[test]
+fn it_validates_nested_brackets() {
+    let pda = setup_pda();
+
+    {
+        let string = String::from("f(a[1], \"(]\", [g(b)])");
+        let mut journal = Vec::new();
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            journal.push((effect, input_data.index));
+        };
+
+        assert!(pda.proceed(&string, Some(&mut logger)).is_ok());
+        assert_eq!(
+            journal.iter().filter(|(effect, _)| *effect == Effect::Open).count(),
+            4
+        );
+        assert_eq!(journal.last(), Some(&(Effect::Finish, string.len())));
+    }
+
+    {
+        let string = String::from("f(a[1)]");
+
+        assert!(matches!(
+            pda.proceed(&string, None),
+            Err(FSMError::NoValidTransition { from: State::Code, input_data })
+                if input_data.index == 5
+        ));
+    }
+
+    {
+        let string = String::from("f([a]");
+        let mut finished = false;
+        let mut finisher = |effect: Effect, _: StreamData| {
+            if effect == Effect::Finish {
+                finished = true;
+            }
+        };
+
+        assert!(matches!(
+            pda.proceed(&string, Some(&mut finisher)),
+            Err(FSMError::NonEmptyStack { state: State::Code, depth: 1 })
+        ));
+        assert!(!finished);
+    }
+}
+
+#[test]
+fn it_reports_stack_underflow() {
+    let pda: PDA<State, Bracket, Effect> = PDA::new(
+        State::Code,
+        map!(
+            State::Code => vec![
+                PDATransition::new(
+                    State::Code, Some(is_round_open), None,
+                    StackOp::Push(Bracket::Round), vec![]
+                ),
+                PDATransition::new(
+                    State::Code, Some(is_round_close), None,
+                    StackOp::Pop, vec![]
+                )
+            ]
+        ),
+        vec![]
+    ).unwrap();
+
+    assert!(pda.proceed(&String::from("(()())"), None).is_ok());
+
+    let string = String::from("(()))");
+
+    assert!(matches!(
+        pda.proceed(&string, None),
+        Err(FSMError::StackUnderflow { from: State::Code, input_data })
+            if input_data.index == 4
+    ));
+}
+
+#[test]
+fn it_extends_transitions_of_fsm() {
+    const OPENED: usize = 0;
+
+    // at most 2 brackets are opened in total
+    let pda: PDA<State, Bracket, Effect> = PDA::new(
+        State::Code,
+        map!(
+            State::Code => vec![
+                PDATransition::extending(
+                    Transition::literal(State::Code, '(', vec![Effect::Open])
+                        .testing(RegisterTest::Less(OPENED, 2))
+                        .updating(RegisterUpdate::Increment(OPENED)),
+                    None,
+                    StackOp::Push(Bracket::Round)
+                ),
+                PDATransition::extending(
+                    Transition::literal(State::Code, ')', vec![Effect::Close]),
+                    Some(Bracket::Round),
+                    StackOp::Pop
+                )
+            ]
+        ),
+        vec![]
+    ).unwrap();
+
+    assert!(pda.proceed(&String::from("(())"), None).is_ok());
+
+    let string = String::from("()()()");
+
+    assert!(matches!(
+        pda.proceed(&string, None),
+        Err(FSMError::NoValidTransition { from: State::Code, input_data })
+            if input_data.index == 4
+    ));
+}
+
+/// Goes to given state after every effect
+struct Redirector(State);
+
+impl Effector<Effect, State> for Redirector {
+    fn dispatch(&mut self, _effect: Effect, _input_data: StreamData) -> Control<State> {
+        Control::GoTo(self.0)
+    }
+}
+
+#[test]
+fn it_checks_states_that_run_goes_to() {
+    // "Quoted" doesn't belong to PDA
+    let pda: PDA<State, Bracket, Effect> = PDA::new(
+        State::Code,
+        map!(
+            State::Code => vec![
+                PDATransition::new(
+                    State::Code, Some(is_round_open), None,
+                    StackOp::Push(Bracket::Round), vec![Effect::Open]
+                ),
+                PDATransition::new(
+                    State::Code, Some(is_round_close), Some(Bracket::Round),
+                    StackOp::Pop, vec![Effect::Close]
+                ),
+                PDATransition::new(
+                    State::Quoted, Some(is_quote), None,
+                    StackOp::Keep, vec![]
+                )
+            ]
+        ),
+        vec![Effect::Finish]
+    ).unwrap();
+
+    assert!(matches!(
+        pda.proceed(&String::from("\"a"), None),
+        Err(FSMError::StateDoesNotExist(State::Quoted))
+    ));
+
+    let string = String::from("()");
+
+    {
+        let mut redirector = Redirector(State::Code);
+        assert!(pda.proceed(&string, Some(&mut redirector)).is_ok());
+    }
+
+    {
+        let mut redirector = Redirector(State::Quoted);
+
+        assert!(matches!(
+            pda.proceed(&string, Some(&mut redirector)),
+            Err(FSMError::StateDoesNotExist(State::Quoted))
+        ));
+    }
+
+    {
+        // the only effect is post-effect
+        let mut redirector = Redirector(State::Quoted);
+
+        assert!(matches!(
+            pda.proceed(&String::new(), Some(&mut redirector)),
+            Err(FSMError::StateDoesNotExist(State::Quoted))
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::Transition;
+use crate::registers::{RegisterTest, RegisterUpdate, RegisterIssue};
+
+use super::utils::test_valid_string;
+
+const DIGITS: usize = 0;
+const LENGTH: usize = 1;
+
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Debug)]
+enum State {
+    Text,
+    Escape,
+    Unicode
+}
+
+fn is_backslash(ch: char) -> bool {
+    ch == '\\'
+}
+
+fn is_u(ch: char) -> bool {
+    ch == 'u'
+}
+
+fn is_hex_digit(ch: char) -> bool {
+    ch.is_ascii_hexdigit()
+}
+
+/// Validates "\uXXXX" escapes (exactly 4 hex digits, also at the end of text)
+/// in text of at most 16 characters (every escape is one character)
+fn setup_fsm() -> FSM<State, ()> {
+    let mut fsm = FSM::new(
+        State::Text,
+        map!(
+            State::Text => vec![
+                Transition::new(State::Escape, Some(is_backslash), None)
+                    .testing(RegisterTest::Less(LENGTH, 16))
+                    .updating(RegisterUpdate::Increment(LENGTH)),
+                Transition::new(State::Text, None, None)
+                    .testing(RegisterTest::Less(LENGTH, 16))
+                    .updating(RegisterUpdate::Increment(LENGTH))
+            ],
+            State::Escape => vec![
+                Transition::new(State::Unicode, Some(is_u), None)
+                    .updating(RegisterUpdate::Reset(DIGITS))
+            ],
+            State::Unicode => vec![
+                Transition::new(State::Unicode, Some(is_hex_digit), None)
+                    .testing(RegisterTest::Less(DIGITS, 4))
+                    .updating(RegisterUpdate::Increment(DIGITS)),
+                Transition::new(State::Escape, Some(is_backslash), None)
+                    .testing(RegisterTest::Equal(DIGITS, 4))
+                    .testing(RegisterTest::Less(LENGTH, 16))
+                    .updating(RegisterUpdate::Increment(LENGTH)),
+                Transition::new(State::Text, None, None)
+                    .testing(RegisterTest::Equal(DIGITS, 4))
+                    .testing(RegisterTest::Less(LENGTH, 16))
+                    .updating(RegisterUpdate::Increment(LENGTH))
+            ]
+        ),
+        None
+    ).unwrap();
+
+    assert!(fsm.add_end_test(State::Unicode, RegisterTest::Equal(DIGITS, 4)).is_ok());
+
+    fsm
+}
+
+#[test]
+fn it_counts_repetitions_with_registers() {
+    let fsm = setup_fsm();
+
+    {
+        let string = String::from("a\\u00e9b\\u0041\\u0042");
+
+        test_valid_string(&fsm, &string, None);
+    }
+
+    {
+        let string = String::from("a\\u00e9b\\u0041");
+
+        test_valid_string(&fsm, &string, None);
+    }
+
+    {
+        let string = String::from("a\\u0e9z");
+
+        assert!(matches!(
+            fsm.proceed(&string, None),
+            Err(FSMError::NoValidTransition { from: State::Unicode, input_data })
+                if input_data.index == 6
+        ));
+    }
+
+    {
+        let string = String::from("a\\u00\\u0041");
+
+        assert!(matches!(
+            fsm.proceed(&string, None),
+            Err(FSMError::NoValidTransition { from: State::Unicode, input_data })
+                if input_data.index == 5
+        ));
+    }
+
+    {
+        let string = String::from("a\\u00");
+
+        assert!(matches!(
+            fsm.proceed(&string, None),
+            Err(FSMError::RejectedEnd { state: State::Unicode, index: 5 })
+        ));
+    }
+
+    {
+        // escapes are single characters
+        let string = String::from("\\u0041\\u0042\\u0043abcdefghijklm");
+
+        test_valid_string(&fsm, &string, None);
+
+        let string = String::from("\\u0041\\u0042\\u0043abcdefghijklmn");
+
+        assert!(matches!(
+            fsm.proceed(&string, None),
+            Err(FSMError::NoValidTransition { from: State::Text, input_data })
+                if input_data.index == 31
+        ));
+    }
+
+    {
+        let string = String::from("the quick brown fox");
+
+        assert!(matches!(
+            fsm.proceed(&string, None),
+            Err(FSMError::NoValidTransition { from: State::Text, input_data })
+                if input_data.index == 16
+        ));
+    }
+}
+
+#[test]
+fn it_analyzes_register_bounds() {
+    assert!(setup_fsm().analyze_registers().is_empty());
+
+    let mut fsm: FSM<State, ()> = FSM::new(
+        State::Text,
+        map!(
+            State::Text => vec![
+                Transition::new(State::Escape, None, None)
+                    .testing(RegisterTest::Greater(DIGITS, 2))
+                    .testing(RegisterTest::LessOrEqual(DIGITS, 2)),
+                Transition::new(State::Text, None, None)
+                    .updating(RegisterUpdate::Increment(LENGTH))
+            ],
+            State::Escape => vec![
+                Transition::new(State::Text, None, None)
+            ],
+            State::Unicode => vec![
+                Transition::new(State::Text, None, None)
+                    .testing(RegisterTest::Less(DIGITS, 0))
+            ]
+        ),
+        None
+    ).unwrap();
+
+    assert!(fsm.add_end_test(State::Escape, RegisterTest::Equal(DIGITS, 4)).is_ok());
+
+    // issues of transitions are ordered by state
+    assert_eq!(
+        fsm.analyze_registers(),
+        vec![
+            RegisterIssue::UnsatisfiableTests { from: State::Text, index: 0, register: DIGITS },
+            RegisterIssue::UnsatisfiableTests { from: State::Unicode, index: 0, register: DIGITS },
+            RegisterIssue::NeverIncremented(DIGITS),
+            RegisterIssue::NeverTested(LENGTH)
+        ]
+    );
+}
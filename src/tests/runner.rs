@@ -0,0 +1,170 @@
+#![cfg(test)]
+
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, StreamData};
+use crate::runner::Runner;
+use crate::compiled::CompiledFSM;
+use crate::byte_dfa::ByteDFA;
+use crate::lexer::Lexer;
+use crate::search::Searcher;
+
+use super::utils::{is_letter, is_digit};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Space,
+    Word,
+    Number
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    Letter,
+    Digit,
+    Done
+}
+
+fn is_space(ch: char) -> bool {
+    ch == ' '
+}
+
+fn setup_fsm() -> FSM<State, Effect> {
+    FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Space, Some(is_space), None),
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit))
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Space, Some(is_space), None)
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit)),
+                Transition::new(State::Space, Some(is_space), None)
+            ]
+        ),
+        Some(Effect::Done)
+    ).unwrap()
+}
+
+static SHARED: OnceLock<FSM<State, Effect>> = OnceLock::new();
+
+fn is_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn it_is_shareable_between_threads() {
+    is_send_sync::<FSM<State, Effect>>();
+    // guards read context only by reference
+    is_send_sync::<FSM<State, Effect, Rc<()>, Rc<()>>>();
+    is_send_sync::<Runner<State, Effect>>();
+    is_send_sync::<CompiledFSM<State, Effect>>();
+    is_send_sync::<ByteDFA<State, Effect>>();
+    is_send_sync::<Lexer<State, usize, Effect>>();
+    is_send_sync::<Searcher<State, Effect>>();
+
+    let fsm = Arc::new(setup_fsm());
+
+    let handles: Vec<_> = (0..4)
+        .map(|thread_id| {
+            let fsm = Arc::clone(&fsm);
+
+            thread::spawn(move || {
+                let string = "ab 12 ".repeat(thread_id + 1);
+                let mut letters = 0;
+                let mut counter = |effect: Effect, _: StreamData| {
+                    if effect == Effect::Letter {
+                        letters += 1;
+                    }
+                };
+
+                assert!(fsm.proceed(&string, Some(&mut counter)).is_ok());
+                letters
+            })
+        })
+        .collect();
+
+    let letters: Vec<usize> = handles.into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    assert_eq!(letters, vec![2, 4, 6, 8]);
+
+    let fsm = SHARED.get_or_init(setup_fsm);
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let string = String::from("ab 12");
+                assert!(fsm.proceed(&string, None).is_ok());
+            });
+        }
+    });
+}
+
+#[test]
+fn it_runs_item_by_item() {
+    let fsm = setup_fsm();
+    let string = String::from("ab 12");
+
+    let mut expected = Vec::new();
+    let mut actual = Vec::new();
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            expected.push((effect, input_data.index));
+        };
+        assert!(fsm.proceed(&string, Some(&mut logger)).is_ok());
+    }
+
+    {
+        let mut logger = |effect: Effect, input_data: StreamData| {
+            actual.push((effect, input_data.index));
+        };
+
+        let mut runner = Runner::start(&fsm, None).unwrap();
+        assert!(runner.is_empty());
+
+        for ch in string.chars() {
+            assert!(runner.feed(ch, Some(&mut logger)).is_ok());
+        }
+
+        assert_eq!(runner.len(), 5);
+        assert_eq!(runner.state(), State::Number);
+        assert!(matches!(runner.finish(Some(&mut logger)), Ok(State::Number)));
+    }
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn it_isolates_runs() {
+    let fsm = setup_fsm();
+
+    let mut word = Runner::start(&fsm, None).unwrap();
+    let mut number = Runner::start(&fsm, None).unwrap();
+
+    assert!(word.feed('a', None).is_ok());
+    assert!(number.feed('1', None).is_ok());
+
+    assert!(matches!(
+        word.feed('1', None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 1
+    ));
+
+    // failed run is over
+    assert!(word.is_finished());
+    assert!(word.is_failed());
+    assert!(matches!(word.feed('b', None), Err(FSMError::RunFailed)));
+    assert_eq!(word.state(), State::Word);
+    assert!(matches!(word.finish(None), Err(FSMError::RunFailed)));
+
+    assert!(number.feed('2', None).is_ok());
+    assert!(!number.is_failed());
+    assert!(matches!(number.finish(None), Ok(State::Number)));
+}
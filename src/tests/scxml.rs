@@ -0,0 +1,283 @@
+#![cfg(test)]
+
+use crate::map::Map;
+use crate::fsm::FSM;
+use crate::types::{Transition, Effector, Control, StreamData, StatesConnection};
+use crate::scxml::{ScxmlVocabulary, ScxmlError, to_scxml, from_scxml};
+
+use super::utils::{test_valid_string, is_digit, is_letter, is_dot};
+use super::automatas::words_and_numbers::*;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    CountWord,
+    CountNumber,
+    Finish
+}
+
+#[derive(Default)]
+struct Counter {
+    word_count: usize,
+    number_count: usize,
+    finished: bool
+}
+
+impl Effector<Effect, State> for Counter {
+    fn dispatch(&mut self, effect: Effect, _data: StreamData) -> Control<State> {
+        match effect {
+            Effect::CountWord => self.word_count += 1,
+            Effect::CountNumber => self.number_count += 1,
+            Effect::Finish => self.finished = true
+        }
+
+        Control::Continue
+    }
+}
+
+fn setup_effects() -> Map<StatesConnection<State>, Vec<Vec<Effect>>> {
+    map!(
+        StatesConnection {
+            from: State::INIT,
+            to: State::WORD
+        } => vec![vec![Effect::CountWord]],
+
+        StatesConnection {
+            from: State::INIT,
+            to: State::NUMBER_IP
+        } => vec![vec![Effect::CountNumber]],
+
+        StatesConnection {
+            from: State::WORD,
+            to: State::NUMBER_IP
+        } => vec![vec![Effect::CountNumber]],
+
+        StatesConnection {
+            from: State::NUMBER_IP,
+            to: State::WORD
+        } => vec![vec![Effect::CountWord]],
+
+        StatesConnection {
+            from: State::NUMBER_FP,
+            to: State::WORD
+        } => vec![vec![Effect::CountWord]],
+    )
+}
+
+fn setup_vocabulary() -> ScxmlVocabulary<State, Effect> {
+    ScxmlVocabulary::new()
+        .state(State::INIT, "init")
+        .state(State::WORD, "word")
+        .state(State::NUMBER_IP, "number_ip")
+        .state(State::NUMBER_FP, "number_fp")
+        .condition("is_letter", is_letter)
+        .condition("is_digit", is_digit)
+        .condition("is_dot", is_dot)
+        .effect(Effect::CountWord, "count_word")
+        .effect(Effect::CountNumber, "count_number")
+        .effect(Effect::Finish, "finish")
+}
+
+fn import(
+    document: &str,
+    vocabulary: &ScxmlVocabulary<State, Effect>
+) -> Result<FSM<State, Effect>, ScxmlError<State>> {
+    from_scxml(document, vocabulary)
+}
+
+#[test]
+fn it_exports_and_imports_fsm() {
+    let effects = setup_effects();
+    let fsm = init_fsm(Some(&effects), Some(Effect::Finish));
+    let vocabulary = setup_vocabulary();
+
+    let document = to_scxml(&fsm, &vocabulary);
+    assert!(document.is_ok());
+
+    let document = document.unwrap();
+
+    assert!(document.contains("initial=\"init\""));
+    assert!(document.contains("<data id=\"post_effect\" expr=\"['finish']\"/>"));
+    assert!(document.contains(
+        "<transition cond=\"is_letter\" target=\"word\">\n            <send event=\"count_word\"/>"
+    ));
+    assert!(document.contains("<transition target=\"init\"/>"));
+
+    let imported = import(&document, &vocabulary);
+    assert!(imported.is_ok());
+
+    let imported = imported.unwrap();
+
+    assert_eq!(to_scxml(&imported, &vocabulary).ok(), Some(document));
+
+    let string = String::from("Add 1.5 pinches of salt and 2 cups of water!");
+    let mut counter = Counter::default();
+
+    test_valid_string(&imported, &string, Some(&mut counter));
+
+    assert_eq!(counter.word_count, 8);
+    assert_eq!(counter.number_count, 2);
+    assert!(counter.finished);
+}
+
+#[test]
+fn it_keeps_order_of_several_effects() {
+    let effects = setup_effects();
+    let mut fsm = init_fsm(Some(&effects), Some(Effect::Finish));
+    let vocabulary = setup_vocabulary();
+
+    assert!(fsm.merge_effects(&map!(
+        StatesConnection {
+            from: State::WORD,
+            to: State::NUMBER_IP
+        } => vec![vec![Effect::CountWord]]
+    )).is_ok());
+    fsm.push_post_effect(Effect::CountNumber);
+
+    let document = to_scxml(&fsm, &vocabulary).unwrap();
+
+    assert!(document.contains("<data id=\"post_effect\" expr=\"['finish', 'count_number']\"/>"));
+    assert!(document.contains(
+        "<send event=\"count_number\"/>\n            <send event=\"count_word\"/>"
+    ));
+
+    let imported = import(&document, &vocabulary).unwrap();
+
+    assert_eq!(imported.post_effects(), &vec![Effect::Finish, Effect::CountNumber]);
+    assert_eq!(to_scxml(&imported, &vocabulary).ok(), Some(document));
+}
+
+#[test]
+fn it_reports_unsupported_documents() {
+    let vocabulary = setup_vocabulary();
+
+    {
+        let fsm = init_fsm::<Effect>(None, None);
+        let vocabulary = ScxmlVocabulary::new()
+            .state(State::INIT, "init")
+            .state(State::WORD, "word")
+            .state(State::NUMBER_IP, "number_ip")
+            .state(State::NUMBER_FP, "number_fp");
+
+        assert!(matches!(
+            to_scxml(&fsm, &vocabulary),
+            Err(ScxmlError::UnnamedCondition { .. })
+        ));
+    }
+
+    {
+        let document = "<scxml initial=\"init\">\
+            <state id=\"init\"><state id=\"word\"/></state>\
+        </scxml>";
+
+        assert!(matches!(
+            import(document, &vocabulary),
+            Err(ScxmlError::Unsupported(_))
+        ));
+    }
+
+    {
+        let document = "<scxml initial=\"init\">\
+            <state id=\"init\"><transition event=\"tick\" target=\"init\"/></state>\
+        </scxml>";
+
+        assert!(matches!(
+            import(document, &vocabulary),
+            Err(ScxmlError::Unsupported(_))
+        ));
+    }
+
+    {
+        let document = "<scxml initial=\"init\">\
+            <state id=\"init\"><transition cond=\"is_space\" target=\"init\"/></state>\
+        </scxml>";
+
+        assert!(matches!(
+            import(document, &vocabulary),
+            Err(ScxmlError::UnknownCondition(ref name)) if name == "is_space"
+        ));
+    }
+
+    {
+        let document = "<scxml initial=\"init\">\
+            <state id=\"init\"><transition target=\"word\"/></state>\
+        </scxml>";
+
+        assert!(matches!(
+            import(document, &vocabulary),
+            Err(ScxmlError::UnknownState(ref name)) if name == "word"
+        ));
+    }
+
+    {
+        let document = "<scxml><state id=\"init\"></scxml>";
+
+        assert!(matches!(
+            import(document, &vocabulary),
+            Err(ScxmlError::Syntax { .. })
+        ));
+    }
+
+    {
+        // deep nesting is rejected instead of overflowing stack
+        let document = "<a>".repeat(100_000) + &"</a>".repeat(100_000);
+
+        assert!(matches!(
+            import(&document, &vocabulary),
+            Err(ScxmlError::Syntax { reason, .. }) if reason == "elements are nested too deeply"
+        ));
+    }
+}
+
+#[test]
+fn it_refers_to_conditions_by_names() {
+    let vocabulary = setup_vocabulary();
+
+    {
+        // condition is registered, but transition doesn't name it
+        let fsm: FSM<State, Effect> = FSM::new(
+            State::INIT,
+            map!(
+                State::INIT => vec![
+                    Transition::new(State::INIT, Some(is_letter), None)
+                ]
+            ),
+            None
+        ).unwrap();
+
+        assert!(matches!(
+            to_scxml(&fsm, &vocabulary),
+            Err(ScxmlError::UnnamedCondition { from: State::INIT, index: 0 })
+        ));
+    }
+
+    {
+        let document = "<scxml initial=\"init\">\
+            <state id=\"init\"><transition cond=\"is_digit\" target=\"init\"/></state>\
+        </scxml>";
+
+        let imported = import(document, &vocabulary).unwrap();
+        let transition = &imported.transitions(State::INIT).unwrap()[0];
+
+        assert_eq!(transition.condition_name, Some("is_digit"));
+        assert!(to_scxml(&imported, &vocabulary).unwrap().contains("cond=\"is_digit\""));
+    }
+}
+
+#[test]
+fn it_rejects_names_that_break_post_effects() {
+    let fsm = init_fsm(None, Some(Effect::Finish));
+    let vocabulary = ScxmlVocabulary::new()
+        .state(State::INIT, "init")
+        .state(State::WORD, "word")
+        .state(State::NUMBER_IP, "number_ip")
+        .state(State::NUMBER_FP, "number_fp")
+        .condition("is_letter", is_letter)
+        .condition("is_digit", is_digit)
+        .condition("is_dot", is_dot)
+        .effect(Effect::Finish, "finish, 'count_word'");
+
+    assert!(matches!(
+        to_scxml(&fsm, &vocabulary),
+        Err(ScxmlError::InvalidName(ref name)) if name == "finish, 'count_word'"
+    ));
+}
@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use crate::fsm::FSM;
+use crate::types::Transition;
+use crate::search::{Searcher, MatchKind};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Start,
+    A,
+    AB,
+    AA
+}
+
+fn is_a(ch: char) -> bool {
+    ch == 'a'
+}
+
+fn is_b(ch: char) -> bool {
+    ch == 'b'
+}
+
+/// Recognizes "ab+"
+fn setup_repeated_b() -> FSM<State, ()> {
+    FSM::new(
+        State::Start,
+        map!(
+            State::Start => vec![
+                Transition::new(State::A, Some(is_a), None)
+            ],
+            State::A => vec![
+                Transition::new(State::AB, Some(is_b), None)
+            ],
+            State::AB => vec![
+                Transition::new(State::AB, Some(is_b), None)
+            ]
+        ),
+        None
+    ).unwrap()
+}
+
+/// Recognizes "aa"
+fn setup_double_a() -> FSM<State, ()> {
+    FSM::new(
+        State::Start,
+        map!(
+            State::Start => vec![
+                Transition::new(State::A, Some(is_a), None)
+            ],
+            State::A => vec![
+                Transition::new(State::AA, Some(is_a), None)
+            ],
+            State::AA => vec![]
+        ),
+        None
+    ).unwrap()
+}
+
+/// Recognizes "a" by the first transition and "a+b" by the second one
+fn setup_overlapping() -> FSM<State, ()> {
+    FSM::new(
+        State::Start,
+        map!(
+            State::Start => vec![
+                Transition::literal(State::AA, 'a', vec![]),
+                Transition::new(State::A, Some(is_a), None)
+            ],
+            State::A => vec![
+                Transition::new(State::A, Some(is_a), None),
+                Transition::new(State::AB, Some(is_b), None)
+            ],
+            State::AA => vec![],
+            State::AB => vec![]
+        ),
+        None
+    ).unwrap()
+}
+
+#[test]
+fn it_finds_leftmost_shortest_and_longest_matches() {
+    let fsm = setup_repeated_b();
+    let searcher = Searcher::new(&fsm, vec![State::AB].into_iter().collect()).unwrap();
+    let text = "xabbb abb éab";
+
+    let shortest: Vec<_> = searcher.find_iter(text, MatchKind::LeftmostShortest, false).collect();
+    let first: Vec<_> = searcher.find_iter(text, MatchKind::LeftmostFirst, false).collect();
+    let longest: Vec<_> = searcher.find_iter(text, MatchKind::LeftmostLongest, false).collect();
+
+    assert_eq!(shortest, vec![1..3, 6..8, 12..14]);
+    assert_eq!(first, vec![1..5, 6..9, 12..14]);
+    assert_eq!(longest, first);
+    assert_eq!(searcher.find(text, MatchKind::LeftmostLongest), Some(1..5));
+    assert!(searcher.is_match("aab"));
+    assert!(!searcher.is_match("ba a"));
+}
+
+#[test]
+fn it_finds_overlapping_matches() {
+    let fsm = setup_double_a();
+    let searcher = Searcher::new(&fsm, vec![State::AA].into_iter().collect()).unwrap();
+
+    let non_overlapping: Vec<_> = searcher.find_iter("aaaa", MatchKind::LeftmostShortest, false).collect();
+    let overlapping: Vec<_> = searcher.find_iter("aaaa", MatchKind::LeftmostShortest, true).collect();
+
+    assert_eq!(non_overlapping, vec![0..2, 2..4]);
+    assert_eq!(overlapping, vec![0..2, 1..3, 2..4]);
+}
+
+#[test]
+fn it_finds_every_overlapping_match() {
+    let fsm = setup_repeated_b();
+    let searcher = Searcher::new(&fsm, vec![State::AB].into_iter().collect()).unwrap();
+
+    let shortest: Vec<_> = searcher.find_iter("abbxab", MatchKind::LeftmostShortest, true).collect();
+    let longest: Vec<_> = searcher.find_iter("abbxab", MatchKind::LeftmostLongest, true).collect();
+
+    assert_eq!(shortest, vec![0..2, 0..3, 4..6]);
+    assert_eq!(longest, shortest);
+}
+
+#[test]
+fn it_finds_first_and_longest_matches_of_overlapping_transitions() {
+    let fsm = setup_overlapping();
+    let searcher = Searcher::new(&fsm, vec![State::AA, State::AB].into_iter().collect()).unwrap();
+
+    // the first transition wins like in "FSM::proceed", so "a+b" isn't reached
+    let first: Vec<_> = searcher.find_iter("aab a", MatchKind::LeftmostFirst, false).collect();
+    let longest: Vec<_> = searcher.find_iter("aab a", MatchKind::LeftmostLongest, false).collect();
+
+    assert_eq!(first, vec![0..1, 1..2, 4..5]);
+    assert_eq!(longest, vec![0..3, 4..5]);
+    assert_eq!(searcher.find("aab", MatchKind::LeftmostShortest), Some(0..1));
+}
@@ -1,8 +1,7 @@
 #![cfg(test)]
 
-use std::collections::HashMap;
-use crate::types::{Effector, StreamData, StatesConnection};
-use crate::macros;
+use crate::map::Map;
+use crate::types::{Effector, Control, StreamData, StatesConnection};
 use super::utils::test_valid_string;
 use super::automatas::words_and_numbers::*;
 
@@ -26,7 +25,7 @@ enum BasicEffect {
     CleanupBuffers,
 }
 
-type Effect = (BasicEffect, Option<BasicEffect>);
+type Effect = BasicEffect;
 
 impl Store {
     pub fn new() -> Self {
@@ -79,7 +78,7 @@ impl Store {
     }
 
     pub fn cleanup_buffers(&mut self) {
-        if self.word_buffer.len() > 0 {
+        if !self.word_buffer.is_empty() {
             let word = self.word_buffer.clone();
             self.words.push(word);
             self.word_buffer.clear();
@@ -91,10 +90,10 @@ impl Store {
 
     pub fn apply_effect(&mut self, effect: BasicEffect, input_data: StreamData) {
         match effect {
-            BasicEffect::PushToWordbuffer => self.push_to_wordbuff(input_data.character),
+            BasicEffect::PushToWordbuffer => self.push_to_wordbuff(input_data.character.unwrap()),
             BasicEffect::AcceptWordbuffer => self.accept_wordbuffer(),
             BasicEffect::PushToNumbuffer { is_fpd } => self.push_to_numbuffer(
-                input_data.character, is_fpd
+                input_data.character.unwrap(), is_fpd
             ),
             BasicEffect::AcceptNumbuffer => self.accept_numbuffer(),
             BasicEffect::CleanupBuffers => self.cleanup_buffers(),
@@ -102,115 +101,94 @@ impl Store {
     }
 }
 
-impl Effector<Effect> for Store {
-    fn dispatch(&mut self, effect: Effect, input_data: StreamData) {
-        self.apply_effect(effect.0, input_data);
-
-        if let Some(effect) = effect.1 {
-            self.apply_effect(effect, input_data)
-        }
+impl Effector<Effect, State> for Store {
+    fn dispatch(&mut self, effect: Effect, input_data: StreamData) -> Control<State> {
+        self.apply_effect(effect, input_data);
+        Control::Continue
     }
 }
 
-fn setup_effects() -> HashMap<StatesConnection<State>, Vec<Effect>> {
+fn setup_effects() -> Map<StatesConnection<State>, Vec<Vec<Effect>>> {
     map!(
         StatesConnection {
             from: State::INIT,
             to: State::WORD
-        } => vec![
-            (BasicEffect::PushToWordbuffer, None)
-        ],
+        } => vec![vec![
+            BasicEffect::PushToWordbuffer
+        ]],
 
         StatesConnection {
             from: State::INIT,
             to: State::NUMBER_IP
-        } => vec![
-            (BasicEffect::PushToNumbuffer { is_fpd: false }, None)
-        ],
+        } => vec![vec![
+            BasicEffect::PushToNumbuffer { is_fpd: false }
+        ]],
 
         StatesConnection {
             from: State::WORD,
             to: State::WORD
-        } => vec![
-            (BasicEffect::PushToWordbuffer, None)
-        ],
+        } => vec![vec![
+            BasicEffect::PushToWordbuffer
+        ]],
 
         StatesConnection {
             from: State::WORD,
             to: State::NUMBER_IP
-        } => vec![
-            (
-                BasicEffect::AcceptWordbuffer,
-                Some(BasicEffect::PushToNumbuffer { is_fpd: false })
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::AcceptWordbuffer,
+            BasicEffect::PushToNumbuffer { is_fpd: false }
+        ]],
 
         StatesConnection {
             from: State::WORD,
             to: State::INIT
-        } => vec![
-            (BasicEffect::AcceptWordbuffer, None)
-        ],
+        } => vec![vec![
+            BasicEffect::AcceptWordbuffer
+        ]],
 
         StatesConnection {
             from: State::NUMBER_IP,
             to: State::WORD
-        } => vec![
-            (
-                BasicEffect::AcceptNumbuffer,
-                Some(BasicEffect::PushToWordbuffer)
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::AcceptNumbuffer,
+            BasicEffect::PushToWordbuffer
+        ]],
 
         StatesConnection {
             from: State::NUMBER_IP,
             to: State::NUMBER_IP
-        } => vec![
-            (
-                BasicEffect::PushToNumbuffer { is_fpd: false },
-                None
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::PushToNumbuffer { is_fpd: false }
+        ]],
 
         StatesConnection {
             from: State::NUMBER_IP,
             to: State::INIT
-        } => vec![
-            (
-                BasicEffect::AcceptNumbuffer,
-                None
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::AcceptNumbuffer
+        ]],
 
         StatesConnection {
             from: State::NUMBER_FP,
             to: State::WORD
-        } => vec![
-            (
-                BasicEffect::AcceptNumbuffer,
-                Some(BasicEffect::PushToWordbuffer)
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::AcceptNumbuffer,
+            BasicEffect::PushToWordbuffer
+        ]],
 
         StatesConnection {
             from: State::NUMBER_FP,
             to: State::NUMBER_FP
-        } => vec![
-            (
-                BasicEffect::PushToNumbuffer { is_fpd: true },
-                None
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::PushToNumbuffer { is_fpd: true }
+        ]],
 
         StatesConnection {
             from: State::NUMBER_FP,
             to: State::INIT
-        } => vec![
-            (
-                BasicEffect::AcceptNumbuffer,
-                None
-            )
-        ],
+        } => vec![vec![
+            BasicEffect::AcceptNumbuffer
+        ]],
     )
 }
 
@@ -219,7 +197,7 @@ fn it_works_correctly() {
     let effects = setup_effects();
     let fsm = init_fsm::<Effect>(
         Some(&effects), 
-        Some((BasicEffect::CleanupBuffers, None))
+        Some(BasicEffect::CleanupBuffers)
     );
     
     {
@@ -283,3 +261,29 @@ fn it_works_correctly() {
         assert!((sum - 8.5).abs() <= f64::EPSILON);
     }
 }
+
+#[test]
+fn it_splits_adjacent_words_and_numbers() {
+    let effects = setup_effects();
+    let fsm = init_fsm::<Effect>(
+        Some(&effects), 
+        Some(BasicEffect::CleanupBuffers)
+    );
+
+    let mut storage = Store::new();
+    // word -> number -> word -> number (with fraction part) -> word
+    let string = String::from("abc12def3.5gh 7");
+
+    test_valid_string(&fsm, &string, Some(&mut storage));
+
+    assert_eq!(
+        storage.words().clone(),
+        vec![
+            String::from("abc"),
+            String::from("def"),
+            String::from("gh")
+        ]
+    );
+
+    assert!((storage.numbers_sum() - 22.5).abs() <= f64::EPSILON);
+}
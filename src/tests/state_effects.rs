@@ -0,0 +1,168 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, Effector, Control, StreamData, StatesConnection};
+use crate::scxml::{ScxmlVocabulary, to_scxml, from_scxml};
+
+use super::utils::{test_valid_string, is_digit, is_letter, is_dot};
+use super::automatas::words_and_numbers::*;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    MarkWord,
+    AcceptWord,
+    Transit,
+    Finish
+}
+
+#[derive(Default)]
+struct Splitter {
+    word_start: usize,
+    words: Vec<String>,
+    journal: Vec<(Effect, usize)>
+}
+
+impl Effector<Effect, State> for Splitter {
+    fn dispatch(&mut self, effect: Effect, data: StreamData) -> Control<State> {
+        match effect {
+            Effect::MarkWord => self.word_start = data.index,
+            Effect::AcceptWord => self.words.push(
+                data.string.chars()
+                    .skip(self.word_start)
+                    .take(data.index - self.word_start)
+                    .collect()
+            ),
+            _ => {}
+        }
+
+        self.journal.push((effect, data.index));
+
+        Control::Continue
+    }
+}
+
+#[test]
+fn it_dispatches_entry_and_exit_effects() {
+    let mut fsm = init_fsm(None, Some(Effect::Finish));
+
+    assert!(fsm.set_entry_effect(State::WORD, Some(Effect::MarkWord)).is_ok());
+    assert!(fsm.set_exit_effect(State::WORD, Some(Effect::AcceptWord)).is_ok());
+
+    {
+        let string = String::from("the quick1 brown2.5fox");
+        let mut splitter = Splitter::default();
+
+        test_valid_string(&fsm, &string, Some(&mut splitter));
+
+        assert_eq!(
+            splitter.words,
+            vec![
+                String::from("the"),
+                String::from("quick"),
+                String::from("brown"),
+                String::from("fox")
+            ]
+        );
+    }
+
+    assert!(fsm.merge_effects(&map!(
+        StatesConnection {
+            from: State::INIT,
+            to: State::WORD
+        } => vec![vec![Effect::Transit]]
+    )).is_ok());
+    assert!(fsm.set_entry_effect(State::INIT, Some(Effect::MarkWord)).is_ok());
+
+    {
+        let string = String::from(" ab");
+        let mut splitter = Splitter::default();
+
+        test_valid_string(&fsm, &string, Some(&mut splitter));
+
+        assert_eq!(
+            splitter.journal,
+            vec![
+                (Effect::MarkWord, 0),
+                (Effect::Transit, 1),
+                (Effect::MarkWord, 1),
+                (Effect::AcceptWord, 3),
+                (Effect::Finish, 3)
+            ]
+        );
+    }
+}
+
+#[test]
+fn it_keeps_state_effects_in_scxml() {
+    let mut fsm = init_fsm::<Effect>(None, None);
+
+    assert!(fsm.set_entry_effect(State::WORD, Some(Effect::MarkWord)).is_ok());
+    assert!(fsm.set_exit_effect(State::WORD, Some(Effect::AcceptWord)).is_ok());
+
+    let vocabulary = ScxmlVocabulary::new()
+        .state(State::INIT, "init")
+        .state(State::WORD, "word")
+        .state(State::NUMBER_IP, "number_ip")
+        .state(State::NUMBER_FP, "number_fp")
+        .condition("is_letter", is_letter)
+        .condition("is_digit", is_digit)
+        .condition("is_dot", is_dot)
+        .effect(Effect::MarkWord, "mark_word")
+        .effect(Effect::AcceptWord, "accept_word");
+
+    let document = to_scxml(&fsm, &vocabulary).unwrap();
+
+    assert!(document.contains(
+        "<onexit>\n            <send event=\"accept_word\"/>\n        </onexit>"
+    ));
+
+    let imported: FSM<State, Effect> = from_scxml(&document, &vocabulary).unwrap();
+
+    assert_eq!(imported.entry_effect(State::WORD), Some(&Effect::MarkWord));
+    assert_eq!(imported.exit_effect(State::WORD), Some(&Effect::AcceptWord));
+    assert_eq!(imported.entry_effect(State::INIT), None);
+}
+
+#[test]
+fn it_aligns_merged_effects_to_parallel_transitions() {
+    let mut fsm: FSM<State, Effect> = FSM::new(
+        State::INIT,
+        map!(
+            State::INIT => vec![
+                Transition::new(State::WORD, Some(is_letter), None),
+                Transition::new(State::WORD, Some(is_digit), None)
+            ],
+            State::WORD => vec![
+                Transition::new(State::INIT, None, None)
+            ]
+        ),
+        None
+    ).unwrap();
+
+    let connection = StatesConnection {
+        from: State::INIT,
+        to: State::WORD
+    };
+
+    // letter and digit edges get their own effects, then letter edge gets one more
+    assert!(fsm.merge_effects(&map!(connection => vec![vec![Effect::MarkWord], vec![Effect::Transit]])).is_ok());
+    assert!(fsm.merge_effects(&map!(connection => vec![vec![Effect::AcceptWord]])).is_ok());
+
+    for (string, expected) in [
+        ("a", vec![(Effect::MarkWord, 0), (Effect::AcceptWord, 0)]),
+        ("1", vec![(Effect::Transit, 0)])
+    ].iter() {
+        let string = String::from(*string);
+        let mut splitter = Splitter::default();
+
+        test_valid_string(&fsm, &string, Some(&mut splitter));
+
+        assert_eq!(&splitter.journal, expected);
+    }
+
+    // every list needs its own transition
+    assert!(matches!(
+        fsm.merge_effects(&map!(connection => vec![vec![], vec![], vec![Effect::Finish]])),
+        Err(FSMError::TransDoesNotExist(conn)) if conn == connection
+    ));
+}
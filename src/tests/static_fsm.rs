@@ -0,0 +1,210 @@
+#![cfg(test)]
+
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, Effector, Control, StreamData};
+use crate::static_fsm::{StaticFSM, StaticRow, StaticTransition, StateIndex};
+
+use super::utils::{is_letter, is_digit};
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Space,
+    Word,
+    Number,
+    Missing
+}
+
+impl StateIndex for State {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    StartWord,
+    Letter,
+    EndWord,
+    Digit,
+    Done
+}
+
+static WORDS: StaticFSM<State, Effect> = StaticFSM::new(
+    State::Space,
+    &[
+        StaticRow::new(&[
+            StaticTransition::literal(State::Space, ' ', &[]),
+            StaticTransition::new(State::Word, Some(is_letter), &[Effect::Letter]),
+            StaticTransition::new(State::Number, Some(is_digit), &[Effect::Digit])
+        ]),
+        StaticRow::new(&[
+            StaticTransition::new(State::Word, Some(is_letter), &[Effect::Letter]),
+            StaticTransition::literal(State::Space, ' ', &[])
+        ])
+            .entering(&[Effect::StartWord])
+            .leaving(&[Effect::EndWord]),
+        StaticRow::new(&[
+            StaticTransition::new(State::Number, Some(is_digit), &[Effect::Digit]),
+            StaticTransition::literal(State::Space, ' ', &[]),
+            StaticTransition::literal(State::Missing, '!', &[])
+        ])
+    ],
+    &[Effect::Done]
+);
+
+fn setup_fsm() -> FSM<State, Effect> {
+    let mut fsm = FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::literal(State::Space, ' ', vec![]),
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit))
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::literal(State::Space, ' ', vec![])
+            ],
+            State::Number => vec![
+                Transition::new(State::Number, Some(is_digit), Some(Effect::Digit)),
+                Transition::literal(State::Space, ' ', vec![]),
+                Transition::literal(State::Missing, '!', vec![])
+            ]
+        ),
+        Some(Effect::Done)
+    ).unwrap();
+
+    assert!(fsm.set_entry_effect(State::Word, Some(Effect::StartWord)).is_ok());
+    assert!(fsm.set_exit_effect(State::Word, Some(Effect::EndWord)).is_ok());
+
+    fsm
+}
+
+struct WordLimit {
+    words: usize,
+    max_words: usize
+}
+
+impl Effector<Effect, State> for WordLimit {
+    fn dispatch(&mut self, effect: Effect, _: StreamData) -> Control<State> {
+        match effect {
+            Effect::StartWord if self.words == self.max_words => Control::Stop,
+            Effect::StartWord => {
+                self.words += 1;
+                Control::Continue
+            },
+            _ => Control::Continue
+        }
+    }
+}
+
+#[test]
+fn it_runs_like_fsm() {
+    let fsm = setup_fsm();
+
+    for input in ["ab 12 cd", "x", "", "1 ab"].iter() {
+        let string = String::from(*input);
+
+        let mut expected = Vec::new();
+        let mut actual = Vec::new();
+
+        {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                expected.push((effect, input_data.index));
+            };
+            assert!(fsm.proceed(&string, Some(&mut logger)).is_ok());
+        }
+
+        {
+            let mut logger = |effect: Effect, input_data: StreamData| {
+                actual.push((effect, input_data.index));
+            };
+            assert!(WORDS.proceed(&string, Some(&mut logger)).is_ok());
+        }
+
+        assert_eq!(actual, expected);
+    }
+
+    let mut limit = WordLimit {
+        words: 0,
+        max_words: 2
+    };
+    let string = String::from("ab cd ef gh");
+
+    assert!(WORDS.proceed(&string, Some(&mut limit)).is_ok());
+    assert_eq!(limit.words, 2);
+}
+
+#[test]
+fn it_rejects_invalid_input() {
+    let string = String::from("ab1");
+
+    assert!(matches!(
+        WORDS.proceed(&string, None),
+        Err(FSMError::NoValidTransition { from: State::Word, input_data }) if input_data.index == 2
+    ));
+
+    // state without row
+    let string = String::from("12!3");
+
+    assert!(matches!(WORDS.proceed(&string, None), Err(FSMError::StateDoesNotExist(State::Missing))));
+    assert!(matches!(WORDS.validate(), Err(FSMError::StateDoesNotExist(State::Missing))));
+}
+
+#[test]
+fn it_checks_states_that_effector_goes_to() {
+    /// Goes to state without row on given effect
+    struct Redirect(Effect);
+
+    impl Effector<Effect, State> for Redirect {
+        fn dispatch(&mut self, effect: Effect, _: StreamData) -> Control<State> {
+            if effect == self.0 {
+                Control::GoTo(State::Missing)
+            } else {
+                Control::Continue
+            }
+        }
+    }
+
+    let string = String::from("ab");
+
+    assert!(matches!(
+        WORDS.proceed(&string, Some(&mut Redirect(Effect::StartWord))),
+        Err(FSMError::StateDoesNotExist(State::Missing))
+    ));
+    assert!(matches!(
+        WORDS.proceed(&string, Some(&mut Redirect(Effect::Done))),
+        Err(FSMError::StateDoesNotExist(State::Missing))
+    ));
+}
+
+#[test]
+fn it_runs_states_without_hash() {
+    #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+    enum Light {
+        Off,
+        On
+    }
+
+    impl StateIndex for Light {
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    static SWITCH: StaticFSM<Light, ()> = StaticFSM::new(
+        Light::Off,
+        &[
+            StaticRow::new(&[StaticTransition::literal(Light::On, '+', &[])]),
+            StaticRow::new(&[StaticTransition::literal(Light::Off, '-', &[])])
+        ],
+        &[]
+    );
+
+    assert!(SWITCH.validate().is_ok());
+    assert!(SWITCH.proceed(&String::from("+-+"), None).is_ok());
+    assert!(matches!(
+        SWITCH.proceed(&String::from("++"), None),
+        Err(FSMError::NoValidTransition { from: Light::On, .. })
+    ));
+}
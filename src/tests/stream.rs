@@ -0,0 +1,327 @@
+#![cfg(all(test, feature = "async"))]
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures_core::Stream;
+use futures_io::AsyncBufRead;
+use futures_executor::block_on;
+use crate::fsm::{FSM, FSMError};
+use crate::types::{Transition, Effector, Control, StreamData};
+use crate::stream::{AsyncEffector, StreamError};
+
+use super::utils::is_letter;
+
+#[derive(Eq, PartialEq, Clone, Copy, Hash, Debug)]
+enum State {
+    Space,
+    Word,
+    Comment
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    StartWord,
+    Letter,
+    Separate,
+    Done
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+struct TooLongWord {
+    index: usize
+}
+
+fn setup_fsm() -> FSM<State, Effect, TooLongWord> {
+    let mut fsm = FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Space, None, Some(Effect::Separate))
+            ],
+            State::Word => vec![
+                Transition::new(State::Word, Some(is_letter), Some(Effect::Letter)),
+                Transition::new(State::Space, None, Some(Effect::Separate))
+            ],
+            State::Comment => vec![
+                Transition::new(State::Comment, None, None)
+            ]
+        ),
+        Some(Effect::Done)
+    ).unwrap();
+
+    assert!(fsm.set_entry_effect(State::Word, Some(Effect::StartWord)).is_ok());
+
+    fsm
+}
+
+/// Future that is pending once (like waiting for I/O)
+struct YieldNow {
+    is_yielded: bool
+}
+
+impl std::future::Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_yielded {
+            return Poll::Ready(());
+        }
+
+        self.is_yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[derive(Default)]
+struct Logger {
+    log: Vec<(Effect, usize)>,
+    word_length: usize
+}
+
+impl Effector<Effect, State, TooLongWord> for Logger {
+    fn dispatch(&mut self, effect: Effect, data: StreamData) -> Control<State, TooLongWord> {
+        self.log.push((effect, data.index));
+
+        match effect {
+            Effect::Letter => {
+                self.word_length += 1;
+
+                if self.word_length > 3 {
+                    return Control::Error(TooLongWord { index: data.index });
+                }
+
+                Control::Continue
+            },
+            Effect::Separate => {
+                self.word_length = 0;
+
+                match data.character {
+                    Some('!') => Control::Stop,
+                    Some('#') => Control::GoTo(State::Comment),
+                    _ => Control::Continue
+                }
+            },
+            _ => Control::Continue
+        }
+    }
+}
+
+impl AsyncEffector<Effect, State, TooLongWord> for Logger {
+    async fn dispatch(&mut self, effect: Effect, data: StreamData<'_>) -> Control<State, TooLongWord> {
+        YieldNow { is_yielded: false }.await;
+        Effector::dispatch(self, effect, data)
+    }
+}
+
+/// Stream of characters that is pending before every item
+struct Chars {
+    chars: Vec<char>,
+    position: usize,
+    is_ready: bool
+}
+
+impl Chars {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            position: 0,
+            is_ready: false
+        }
+    }
+}
+
+impl Stream for Chars {
+    type Item = char;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<char>> {
+        if !self.is_ready {
+            self.is_ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.is_ready = false;
+        let ch = self.chars.get(self.position).copied();
+        self.position += 1;
+
+        Poll::Ready(ch)
+    }
+}
+
+/// Reader that gives at most two bytes at once and fails at the end
+struct Chunked<'b> {
+    bytes: &'b [u8],
+    is_failing: bool
+}
+
+impl futures_io::AsyncRead for Chunked<'_> {
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context<'_>, _: &mut [u8]) -> Poll<io::Result<usize>> {
+        unimplemented!()
+    }
+}
+
+impl AsyncBufRead for Chunked<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        if this.bytes.is_empty() && this.is_failing {
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset")));
+        }
+
+        Poll::Ready(Ok(&this.bytes[..this.bytes.len().min(2)]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amount: usize) {
+        let this = self.get_mut();
+        this.bytes = &this.bytes[amount..];
+    }
+}
+
+#[test]
+fn it_runs_stream_like_iterator() {
+    let fsm = setup_fsm();
+
+    for input in ["ab c", "ab! cd", "ab #abcdef", "ab abcd ef", ""].iter() {
+        let mut expected = Logger::default();
+        let mut actual = Logger::default();
+
+        let is_expected_ok = fsm.proceed_iter(input.chars(), Some(&mut expected)).is_ok();
+        let is_actual_ok = block_on(fsm.proceed_stream(Chars::new(input), Some(&mut actual))).is_ok();
+
+        assert_eq!(actual.log, expected.log);
+        assert_eq!(is_actual_ok, is_expected_ok);
+    }
+
+    let mut logger = Logger::default();
+
+    assert!(matches!(
+        block_on(fsm.proceed_stream(Chars::new("ab abcd"), Some(&mut logger))),
+        Err(FSMError::Effect(TooLongWord { index: 6 }))
+    ));
+    assert!(block_on(fsm.proceed_stream(Chars::new("ab 1"), None::<&mut Logger>)).is_ok());
+}
+
+#[test]
+fn it_runs_text_of_reader() {
+    let fsm = setup_fsm();
+    let text = "ψω ab";
+
+    let mut expected = Logger::default();
+    let mut actual = Logger::default();
+
+    assert!(fsm.proceed_iter(text.chars(), Some(&mut expected)).is_ok());
+
+    let reader = Chunked {
+        bytes: text.as_bytes(),
+        is_failing: false
+    };
+    assert!(block_on(fsm.proceed_reader(reader, Some(&mut actual))).is_ok());
+    assert_eq!(actual.log, expected.log);
+
+    assert!(block_on(fsm.proceed_reader("ab c".as_bytes(), None::<&mut Logger>)).is_ok());
+}
+
+#[test]
+fn it_fails_on_broken_reader() {
+    let fsm = setup_fsm();
+
+    let reader = Chunked {
+        bytes: b"ab",
+        is_failing: true
+    };
+    assert!(matches!(
+        block_on(fsm.proceed_reader(reader, None::<&mut Logger>)),
+        Err(StreamError::Read(error)) if error.kind() == io::ErrorKind::ConnectionReset
+    ));
+
+    // the first byte of "ψ" and then space
+    let bytes = [b'a', 0xCF, b' '];
+    assert!(matches!(
+        block_on(fsm.proceed_reader(&bytes[..], None::<&mut Logger>)),
+        Err(StreamError::Read(error)) if error.kind() == io::ErrorKind::InvalidData
+    ));
+
+    // text ends inside character
+    let bytes = [b'a', 0xCF];
+    assert!(matches!(
+        block_on(fsm.proceed_reader(&bytes[..], None::<&mut Logger>)),
+        Err(StreamError::Read(error)) if error.kind() == io::ErrorKind::InvalidData
+    ));
+
+    let mut logger = Logger::default();
+    assert!(matches!(
+        block_on(fsm.proceed_reader("abcd".as_bytes(), Some(&mut logger))),
+        Err(StreamError::Run(FSMError::Effect(TooLongWord { index: 3 })))
+    ));
+}
+
+#[test]
+fn it_validates_items_by_context_of_effector() {
+    /// Counter of letters that is read by guards
+    #[derive(Default)]
+    struct Counter {
+        letters: usize
+    }
+
+    impl AsyncEffector<Effect, State, (), char, Counter> for Counter {
+        async fn dispatch(&mut self, effect: Effect, _: StreamData<'_>) -> Control<State> {
+            if effect == Effect::Letter {
+                self.letters += 1;
+            }
+
+            Control::Continue
+        }
+
+        fn context(&self) -> Option<&Counter> {
+            Some(self)
+        }
+    }
+
+    fn has_room(_ch: char, counter: &Counter) -> bool {
+        counter.letters < 2
+    }
+
+    let fsm: FSM<State, Effect, (), Counter> = FSM::new(
+        State::Space,
+        map!(
+            State::Space => vec![
+                Transition::guarded(State::Space, Some(is_letter), has_room, vec![Effect::Letter])
+            ]
+        ),
+        None
+    ).unwrap();
+
+    let mut counter = Counter::default();
+
+    assert!(block_on(fsm.proceed_stream(Chars::new("ab"), Some(&mut counter))).is_ok());
+    assert_eq!(counter.letters, 2);
+
+    assert!(matches!(
+        block_on(fsm.proceed_stream(Chars::new("abc"), Some(&mut Counter::default()))),
+        Err(FSMError::NoValidTransition { from: State::Space, input_data }) if input_data.index == 2
+    ));
+}
+
+#[test]
+fn it_resolves_redirection_after_last_item() {
+    /// Goes to comment after last item
+    struct Redirect;
+
+    impl AsyncEffector<Effect, State, TooLongWord> for Redirect {
+        async fn dispatch(&mut self, effect: Effect, _: StreamData<'_>) -> Control<State, TooLongWord> {
+            match effect {
+                Effect::Done => Control::GoTo(State::Comment),
+                _ => Control::Continue
+            }
+        }
+    }
+
+    let fsm = setup_fsm();
+
+    assert!(matches!(block_on(fsm.proceed_stream(Chars::new("ab"), None::<&mut Logger>)), Ok(State::Word)));
+    assert!(matches!(block_on(fsm.proceed_stream(Chars::new("ab"), Some(&mut Redirect))), Ok(State::Comment)));
+    assert!(matches!(block_on(fsm.proceed_reader("ab".as_bytes(), Some(&mut Redirect))), Ok(State::Comment)));
+}
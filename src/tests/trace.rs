@@ -0,0 +1,150 @@
+#![cfg(test)]
+
+use crate::map::Map;
+use crate::types::{Effector, Control, StreamData, StatesConnection};
+use crate::trace::{Trace, TraceStep, TraceDiff};
+
+use super::automatas::words_and_numbers::*;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Effect {
+    StartWord,
+    StartNumber,
+    Finish
+}
+
+/// Effector that remembers every dispatched effect with its position
+#[derive(Default)]
+struct Journal {
+    entries: Vec<(Effect, usize, Option<char>)>
+}
+
+impl Effector<Effect, State> for Journal {
+    fn dispatch(&mut self, effect: Effect, data: StreamData) -> Control<State> {
+        self.entries.push((effect, data.index, data.character));
+
+        Control::Continue
+    }
+}
+
+fn setup_effects() -> Map<StatesConnection<State>, Vec<Vec<Effect>>> {
+    map!(
+        StatesConnection {
+            from: State::INIT,
+            to: State::WORD
+        } => vec![vec![Effect::StartWord]],
+
+        StatesConnection {
+            from: State::INIT,
+            to: State::NUMBER_IP
+        } => vec![vec![Effect::StartNumber]],
+    )
+}
+
+fn parse_state(name: &str) -> Option<State> {
+    match name {
+        "INIT" => Some(State::INIT),
+        "WORD" => Some(State::WORD),
+        "NUMBER_IP" => Some(State::NUMBER_IP),
+        "NUMBER_FP" => Some(State::NUMBER_FP),
+        _ => None
+    }
+}
+
+fn parse_effect(name: &str) -> Option<Effect> {
+    match name {
+        "StartWord" => Some(Effect::StartWord),
+        "StartNumber" => Some(Effect::StartNumber),
+        "Finish" => Some(Effect::Finish),
+        _ => None
+    }
+}
+
+#[test]
+fn it_records_and_replays_run() {
+    let effects = setup_effects();
+    let fsm = init_fsm(Some(&effects), Some(Effect::Finish));
+    let string = String::from("ab 1.5\tc");
+
+    let mut journal = Journal::default();
+    let mut trace = Trace::new();
+
+    assert!(fsm.proceed_traced(&string, Some(&mut journal), &mut trace).is_ok());
+
+    assert_eq!(trace.steps().len(), 8);
+    assert_eq!(
+        trace.steps()[4],
+        TraceStep {
+            index: 4,
+            character: '.',
+            from: State::NUMBER_IP,
+            transition: 2,
+            to: State::NUMBER_FP,
+            effects: vec![]
+        }
+    );
+    assert_eq!(trace.end_effects(), &vec![Effect::Finish]);
+
+    let mut replayed = Journal::default();
+    assert!(trace.replay(&mut replayed).is_ok());
+
+    assert_eq!(replayed.entries, journal.entries);
+    assert_eq!(
+        replayed.entries,
+        vec![
+            (Effect::StartWord, 0, Some('a')),
+            (Effect::StartNumber, 3, Some('1')),
+            (Effect::StartWord, 7, Some('c')),
+            (Effect::Finish, 8, None)
+        ]
+    );
+
+    println!("{}", trace);
+}
+
+#[test]
+fn it_compares_trace_with_golden_one() {
+    let effects = setup_effects();
+    let fsm = init_fsm(Some(&effects), Some(Effect::Finish));
+    let string = String::from("ab 1\n");
+
+    let mut trace = Trace::new();
+    assert!(fsm.proceed_traced(&string, None, &mut trace).is_ok());
+
+    let golden = trace.serialize();
+
+    assert!(golden.starts_with("input\tab 1\\n\n"));
+    assert!(golden.contains("step\t3\t1\tINIT\t1\tNUMBER_IP\tStartNumber\n"));
+    assert!(golden.ends_with("end\tFinish\n"));
+
+    let restored = Trace::deserialize(&golden, parse_state, parse_effect);
+    assert_eq!(restored.as_ref().ok(), Some(&trace));
+
+    let restored = restored.unwrap();
+    assert_eq!(trace.diff(&restored), None);
+
+    let other_fsm = init_fsm(Some(&effects), None);
+    let mut other_trace = Trace::new();
+    assert!(other_fsm.proceed_traced(&string, None, &mut other_trace).is_ok());
+
+    assert_eq!(
+        other_trace.diff(&restored),
+        Some(TraceDiff::End {
+            expected: vec![Effect::Finish],
+            actual: vec![]
+        })
+    );
+
+    let string = String::from("ab 12");
+    assert!(fsm.proceed_traced(&string, None, &mut other_trace).is_ok());
+
+    assert!(matches!(
+        other_trace.diff(&restored),
+        Some(TraceDiff::Step { position: 4, .. })
+    ));
+
+    assert!(matches!(
+        Trace::deserialize("step\t0\ta\tINIT\t0\tSPACE", parse_state, parse_effect),
+        Err(ref error) if error.line == 1
+    ));
+}
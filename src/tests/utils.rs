@@ -6,17 +6,17 @@ use crate::types::{Effector, StreamData};
 pub fn test_valid_string<'a, State, Effect>(
     fsm: &'a FSM<State, Effect>, 
     string: &'a String,
-    effector: Option<&'a mut dyn Effector<Effect>>
+    effector: Option<&'a mut dyn Effector<Effect, State>>
 ) 
     where State: Eq + PartialEq + Copy + Hash + Debug,
-          Effect: Eq + PartialEq + Copy
+          Effect: Clone
 {
     let result = fsm.proceed(string, effector);
 
-    if result.is_err() {
+    if let Err(err) = result {
         println!(
             "\n========\n\n{:?}\n\n=========\n", 
-            result.unwrap_err()
+            err
         );
     }
 
@@ -28,10 +28,10 @@ pub fn test_invalid_string<'a, State, Effect>(
     string: &'a String,
     index: usize,
     character: char,
-    effector: Option<&'a mut dyn Effector<Effect>>
+    effector: Option<&'a mut dyn Effector<Effect, State>>
 ) 
     where State: Eq + PartialEq + Copy + Hash + Debug,
-          Effect: Eq + PartialEq + Copy
+          Effect: Clone
 {
     let result = fsm.proceed(string, effector);
     assert!(result.is_err());
@@ -42,9 +42,9 @@ pub fn test_invalid_string<'a, State, Effect>(
         matches!(
             error_from_res,
             FSMError::NoValidTransition { 
-                input_data: StreamData { index, character, .. }, 
+                input_data: StreamData { index: err_index, character: err_character, .. }, 
                 .. 
-            }
+            } if err_index == index && err_character == Some(character)
         )
     );
 
@@ -55,10 +55,14 @@ pub fn test_invalid_string<'a, State, Effect>(
 }
 
 pub fn is_letter(ch: char) -> bool {
-    ('A'..='Z').contains(&ch) ||
-        ('a'..='z').contains(&ch)
+    ch.is_ascii_uppercase() ||
+        ch.is_ascii_lowercase()
 }
 
 pub fn is_digit(ch: char) -> bool {
-    ('0'..='9').contains(&ch)
+    ch.is_ascii_digit()
+}
+
+pub fn is_dot(ch: char) -> bool {
+    ch == '.'
 }
@@ -0,0 +1,399 @@
+use core::fmt;
+use core::fmt::Debug;
+use alloc::vec::Vec;
+use alloc::string::{String, ToString};
+use alloc::format;
+use crate::types::{Effector, Control, StreamData};
+
+/// Single step of FSM run (i.e. proceeding of one character or other input item)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraceStep<State, Effect, Input = char>
+    where State: Copy,
+          Effect: Clone
+{
+    /// Character (or item) position in input
+    pub index: usize,
+    /// Proceeded character (or item)
+    pub character: Input,
+    /// State before step
+    pub from: State,
+    /// Position of matched transition in transitions of "from" state
+    /// (inherited transitions of its composite states follow its own ones)
+    pub transition: usize,
+    /// State after step
+    pub to: State,
+    /// Side effects dispatched during step in order of dispatch
+    /// (exit effect of "from", effect of transition, entry effect of "to")
+    pub effects: Vec<Effect>
+}
+
+/// Record of FSM run that is filled by "FSM::proceed_traced"
+/// (or "FSM::proceed_iter_traced" for input of other type)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Trace<State, Effect, Input = char>
+    where State: Copy,
+          Effect: Clone
+{
+    /// Input string of run (empty for input of other type)
+    input: String,
+    /// Side effects dispatched before first character
+    /// (entry effect of initial state)
+    start_effects: Vec<Effect>,
+    /// Steps in order of proceeding
+    steps: Vec<TraceStep<State, Effect, Input>>,
+    /// Side effects dispatched after last character
+    /// (exit effect of last state and post-effect)
+    end_effects: Vec<Effect>
+}
+
+/// First difference between two traces
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TraceDiff<State, Effect, Input = char>
+    where State: Copy,
+          Effect: Clone
+{
+    /// Effects before first character differ
+    Start {
+        expected: Vec<Effect>,
+        actual: Vec<Effect>
+    },
+    /// Steps at the same position differ
+    /// (None if one of traces is shorter)
+    Step {
+        position: usize,
+        expected: Option<TraceStep<State, Effect, Input>>,
+        actual: Option<TraceStep<State, Effect, Input>>
+    },
+    /// Effects after last character differ
+    End {
+        expected: Vec<Effect>,
+        actual: Vec<Effect>
+    }
+}
+
+/// Error that occurs during deserialization of trace
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TraceError {
+    /// Number of malformed line (starting from 1)
+    pub line: usize,
+    /// Description of problem
+    pub reason: &'static str
+}
+
+impl<State, Effect, Input> Trace<State, Effect, Input>
+    where State: Copy,
+          Effect: Clone
+{
+    /// Creates empty trace
+    pub fn new() -> Self {
+        Self {
+            input: String::new(),
+            start_effects: Vec::new(),
+            steps: Vec::new(),
+            end_effects: Vec::new()
+        }
+    }
+
+    /// Returns input string of run
+    pub fn input(&self) -> &String {
+        &self.input
+    }
+
+    /// Returns effects recorded before first character
+    pub fn start_effects(&self) -> &Vec<Effect> {
+        &self.start_effects
+    }
+
+    /// Returns recorded steps
+    pub fn steps(&self) -> &Vec<TraceStep<State, Effect, Input>> {
+        &self.steps
+    }
+
+    /// Returns effects recorded after last character
+    /// (empty if run didn't reach end of input)
+    pub fn end_effects(&self) -> &Vec<Effect> {
+        &self.end_effects
+    }
+
+    pub(crate) fn start(&mut self, input: &str) {
+        self.input.clear();
+        self.input.push_str(input);
+        self.start_effects.clear();
+        self.steps.clear();
+        self.end_effects.clear();
+    }
+
+    pub(crate) fn record_start(&mut self, effect: Effect) {
+        self.start_effects.push(effect);
+    }
+
+    pub(crate) fn record(&mut self, step: TraceStep<State, Effect, Input>) {
+        self.steps.push(step);
+    }
+
+    pub(crate) fn record_end(&mut self, effect: Effect) {
+        self.end_effects.push(effect);
+    }
+
+    /// Dispatches recorded effects into effector in the same order
+    /// (and with the same stream data) as original run did,
+    /// without running any predicates.
+    /// Replay ends early if effector returns "Stop" or "Error";
+    /// "GoTo" is ignored as trace already holds path of original run
+    /// - effector: module that mutates some data by effects
+    pub fn replay<Error>(
+        &self, 
+        effector: &mut dyn Effector<Effect, State, Error, Input>
+    ) -> Result<(), Error> 
+        where Input: Copy + PartialEq
+    {
+        let end_index = self.input.len().max(self.steps.len());
+        let start = self.start_effects.iter()
+            .map(|effect| (effect.clone(), 0, None));
+        let steps = self.steps.iter()
+            .flat_map(|step| step.effects.iter().map(move |effect| (effect.clone(), step.index, Some(step.character))));
+        let end = self.end_effects.iter()
+            .map(|effect| (effect.clone(), end_index, None));
+
+        for (effect, index, character) in start.chain(steps).chain(end) {
+            let control = effector.dispatch(effect, StreamData {
+                string: &self.input,
+                index,
+                character
+            });
+
+            match control {
+                Control::Stop => return Ok(()),
+                Control::Error(error) => return Err(error),
+                Control::Continue | Control::GoTo(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<State, Effect, Input> Trace<State, Effect, Input>
+    where State: Copy + PartialEq,
+          Effect: Clone + PartialEq,
+          Input: Clone + PartialEq
+{
+    /// Finds first difference from expected (golden) trace
+    /// (or None if traces record the same steps and effects)
+    /// - expected: golden trace
+    pub fn diff(&self, expected: &Self) -> Option<TraceDiff<State, Effect, Input>> {
+        if expected.start_effects != self.start_effects {
+            return Some(TraceDiff::Start {
+                expected: expected.start_effects.clone(),
+                actual: self.start_effects.clone()
+            });
+        }
+
+        let length = self.steps.len().max(expected.steps.len());
+
+        for position in 0..length {
+            let expected_step = expected.steps.get(position).cloned();
+            let actual_step = self.steps.get(position).cloned();
+
+            if expected_step != actual_step {
+                return Some(TraceDiff::Step {
+                    position,
+                    expected: expected_step,
+                    actual: actual_step
+                });
+            }
+        }
+
+        if expected.end_effects != self.end_effects {
+            return Some(TraceDiff::End {
+                expected: expected.end_effects.clone(),
+                actual: self.end_effects.clone()
+            });
+        }
+
+        None
+    }
+}
+
+impl<State, Effect> Trace<State, Effect>
+    where State: Copy + Debug,
+          Effect: Clone + Debug
+{
+    /// Serializes trace into tab-separated text
+    /// (states and effects are written by their Debug representation):
+    /// "input" line, "start" line per effect before first character,
+    /// "step" line per step (with its effects as trailing fields)
+    /// and "end" line per effect after last character
+    pub fn serialize(&self) -> String {
+        let mut text = format!("input\t{}\n", escape(&self.input));
+
+        for effect in self.start_effects.iter() {
+            text.push_str(&format!("start\t{:?}\n", effect));
+        }
+
+        for step in self.steps.iter() {
+            text.push_str(&format!(
+                "step\t{}\t{}\t{:?}\t{}\t{:?}",
+                step.index,
+                escape(&step.character.to_string()),
+                step.from,
+                step.transition,
+                step.to
+            ));
+
+            for effect in step.effects.iter() {
+                text.push_str(&format!("\t{:?}", effect));
+            }
+
+            text.push('\n');
+        }
+
+        for effect in self.end_effects.iter() {
+            text.push_str(&format!("end\t{:?}\n", effect));
+        }
+
+        text
+    }
+
+    /// Restores trace from text made by "serialize"
+    /// - text: serialized trace,
+    /// - parse_state: parser of state from its Debug representation,
+    /// - parse_effect: parser of effect from its Debug representation
+    pub fn deserialize(
+        text: &str,
+        parse_state: impl Fn(&str) -> Option<State>,
+        parse_effect: impl Fn(&str) -> Option<Effect>
+    ) -> Result<Self, TraceError> {
+        let mut trace = Self::new();
+
+        for (line_id, line) in text.lines().enumerate() {
+            let error = |reason| TraceError { line: line_id + 1, reason };
+            let fields: Vec<&str> = line.split('\t').collect();
+
+            match fields.as_slice() {
+                [""] => {},
+                ["input", input] => trace.input = unescape(input).ok_or_else(
+                    || error("invalid escape sequence")
+                )?,
+                ["start", effect] => trace.start_effects.push(
+                    parse_effect(effect).ok_or_else(|| error("unknown effect"))?
+                ),
+                ["step", index, character, from, transition, to, effects @ ..] => {
+                    let mut characters = unescape(character).ok_or_else(
+                        || error("invalid escape sequence")
+                    )?.chars().collect::<Vec<char>>();
+
+                    if characters.len() != 1 {
+                        return Err(error("expected single character"));
+                    }
+
+                    trace.steps.push(TraceStep {
+                        index: index.parse().map_err(|_| error("invalid index"))?,
+                        character: characters.remove(0),
+                        from: parse_state(from).ok_or_else(|| error("unknown state"))?,
+                        transition: transition.parse().map_err(
+                            |_| error("invalid transition index")
+                        )?,
+                        to: parse_state(to).ok_or_else(|| error("unknown state"))?,
+                        effects: effects.iter()
+                            .map(|effect| parse_effect(effect).ok_or_else(|| error("unknown effect")))
+                            .collect::<Result<Vec<Effect>, TraceError>>()?
+                    });
+                },
+                ["end", effect] => trace.end_effects.push(
+                    parse_effect(effect).ok_or_else(|| error("unknown effect"))?
+                ),
+                _ => return Err(error("unexpected line"))
+            }
+        }
+
+        Ok(trace)
+    }
+}
+
+impl<State, Effect, Input> Default for Trace<State, Effect, Input>
+    where State: Copy,
+          Effect: Clone
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<State, Effect, Input> fmt::Display for Trace<State, Effect, Input>
+    where State: Copy + Debug,
+          Effect: Clone + Debug,
+          Input: Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "trace of {:?}", self.input)?;
+
+        for effect in self.start_effects.iter() {
+            writeln!(f, "{:>6} / {:?}", "start", effect)?;
+        }
+
+        for step in self.steps.iter() {
+            write!(
+                f,
+                "{:>6} {:<8} {:?} --[{}]--> {:?}",
+                step.index,
+                format!("{:?}", step.character),
+                step.from,
+                step.transition,
+                step.to
+            )?;
+
+            for effect in step.effects.iter() {
+                write!(f, " / {:?}", effect)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        for effect in self.end_effects.iter() {
+            writeln!(f, "{:>6} / {:?}", "end", effect)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            _ => escaped.push(ch)
+        }
+    }
+
+    escaped
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => unescaped.push('\\'),
+            't' => unescaped.push('\t'),
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            '0' => unescaped.push('\0'),
+            _ => return None
+        }
+    }
+
+    Some(unescaped)
+}
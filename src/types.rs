@@ -1,82 +1,248 @@
-use std::hash::Hash;
+use core::hash::Hash;
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::registers::{RegisterTest, RegisterUpdate, register_value};
 
-/// Input character validator
-pub type Predicate = fn(ch: char) -> bool;
+/// Input character (or any other input item) validator
+pub type Predicate<Input = char> = fn(ch: Input) -> bool;
+
+/// Input character (or any other input item) validator 
+/// that also reads user context (e.g. data mutated by effector)
+pub type Guard<Context, Input = char> = fn(ch: Input, context: &Context) -> bool;
 
 /// Transition to next state which is validated by condition
-pub struct Transition<State, Effect> 
+/// (and guard that reads context of type "Context").
+/// Input is type of items of stream (characters by default)
+pub struct Transition<State, Effect, Context = (), Input = char> 
     where State: Eq + PartialEq + Copy,
-          Effect: Copy
+          Effect: Clone
 {
+    /// Item that current character of stream must be equal to
+    /// (checked before condition, e.g. for characters known only at runtime).
+    /// If None then any character may pass
+    pub symbol: Option<Input>,
     /// Predicate that validates current character of stream.
     /// If None then transition is unconditional (i.e. succeeds for every input character)
-    pub condition: Option<Predicate>,
+    pub condition: Option<Predicate<Input>>,
+    /// Name of condition (see "naming"). Exports refer to condition by name,
+    /// so unnamed condition can't be exported
+    pub condition_name: Option<&'static str>,
+    /// Predicate that validates current character with context 
+    /// (checked after condition). 
+    /// Guarded transition never succeeds if run has no context
+    pub guard: Option<Guard<Context, Input>>,
+    /// Tests of registers of run that must hold (checked before symbol, condition and guard)
+    pub tests: Vec<RegisterTest>,
+    /// Updates of registers of run that are applied (in order)
+    /// after successful validation of transition
+    pub updates: Vec<RegisterUpdate>,
     /// Next state
     pub to: State,
-    /// Side effect that is generated after successful validation of transition
-    /// If None then no effect is generated
-    pub effect: Option<Effect>
+    /// Side effects that are generated (in order) after successful validation of transition
+    /// If empty then no effect is generated
+    pub effects: Vec<Effect>
 }
 
 /// Pair of states ("from", "to")
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct StatesConnection<State> 
-    where State: Eq + PartialEq + Copy + Hash,
+    where State: Eq + PartialEq + Copy,
 {
     pub from: State,
     pub to: State
 }
 
-impl<State, Effect> Transition<State, Effect> 
+impl<State, Effect, Context, Input> Transition<State, Effect, Context, Input> 
     where State: Eq + PartialEq + Copy,
-          Effect: Copy
+          Effect: Clone,
+          Input: Copy + PartialEq
 {
-    /// Creates new transition
+    /// Creates new transition with at most one effect
     /// - to: next state,
     /// - condition: predicate for character,
     /// - effect: side effect
-    pub fn new(to: State, condition: Option<Predicate>, effect: Option<Effect>) -> Self {
+    pub fn new(to: State, condition: Option<Predicate<Input>>, effect: Option<Effect>) -> Self {
+        Self::with_effects(to, condition, effect.into_iter().collect())
+    }
+
+    /// Creates new transition with ordered list of effects
+    /// - to: next state,
+    /// - condition: predicate for character,
+    /// - effects: side effects (dispatched in order)
+    pub fn with_effects(to: State, condition: Option<Predicate<Input>>, effects: Vec<Effect>) -> Self {
         Self {
             to,
+            symbol: None,
             condition,
-            effect
+            condition_name: None,
+            guard: None,
+            tests: Vec::new(),
+            updates: Vec::new(),
+            effects
         }
     }
 
-    /// Matches next state and side effect for current character
+    /// Creates new transition that is validated by guard
+    /// - to: next state,
+    /// - condition: predicate for character,
+    /// - guard: predicate for character and context,
+    /// - effects: side effects (dispatched in order)
+    pub fn guarded(
+        to: State, 
+        condition: Option<Predicate<Input>>, 
+        guard: Guard<Context, Input>, 
+        effects: Vec<Effect>
+    ) -> Self {
+        Self {
+            to,
+            symbol: None,
+            condition,
+            condition_name: None,
+            guard: Some(guard),
+            tests: Vec::new(),
+            updates: Vec::new(),
+            effects
+        }
+    }
+
+    /// Creates new transition that matches only given character
+    /// - to: next state,
+    /// - symbol: matched character,
+    /// - effects: side effects (dispatched in order)
+    pub fn literal(to: State, symbol: Input, effects: Vec<Effect>) -> Self {
+        Self {
+            symbol: Some(symbol),
+            ..Self::with_effects(to, None, effects)
+        }
+    }
+
+    /// Names condition of transition (e.g. for SCXML export)
+    /// - name: name of condition (the one it's registered under in vocabulary)
+    pub fn naming(mut self, name: &'static str) -> Self {
+        self.condition_name = Some(name);
+        self
+    }
+
+    /// Adds test of register to transition
+    /// - test: register index and bound
+    pub fn testing(mut self, test: RegisterTest) -> Self {
+        self.tests.push(test);
+        self
+    }
+
+    /// Adds update of register to transition
+    /// - update: register index and operation
+    pub fn updating(mut self, update: RegisterUpdate) -> Self {
+        self.updates.push(update);
+        self
+    }
+
+    /// Checks that every test of transition holds for registers of run
+    /// - registers: values of registers (missing registers are zero)
+    pub fn accepts_registers(&self, registers: &[u64]) -> bool {
+        self.tests.iter()
+            .all(|test| test.holds(register_value(registers, test.register())))
+    }
+
+    /// Matches next state and side effects for current character
+    /// (effects are borrowed, so they are cloned only if they're dispatched).
+    /// Guarded transition doesn't match (see "transit_in")
     /// - ch: current character (of stream) 
-    pub fn transit(&self, ch: char) -> (Option<State>, Option<Effect>) {
-        match self.condition {
-            Some(condition) => {
-                if condition(ch) {
-                    (Some(self.to), self.effect)
-                } else {
-                    (None, None)
-                }
-            },
-            None => (Some(self.to), self.effect)
+    pub fn transit(&self, ch: Input) -> (Option<State>, &[Effect]) {
+        self.transit_in(ch, None)
+    }
+
+    /// Matches next state and side effects for current character in context
+    /// - ch: current character (of stream),
+    /// - context: data read by guard
+    pub fn transit_in(&self, ch: Input, context: Option<&Context>) -> (Option<State>, &[Effect]) {
+        let is_valid = self.symbol.is_none_or(|symbol| symbol == ch) && match self.condition {
+            Some(condition) => condition(ch),
+            None => true
+        };
+
+        let is_guarded = match (self.guard, context) {
+            (Some(guard), Some(context)) => guard(ch, context),
+            (Some(_), None) => false,
+            (None, _) => true
+        };
+
+        if is_valid && is_guarded {
+            (Some(self.to), &self.effects)
+        } else {
+            (None, &[])
         }
     }
 }
 
 /// Information for debugging and effects
 #[derive(Copy, Clone, Debug)]
-pub struct StreamData<'a> {
+pub struct StreamData<'a, Input = char> {
     /// Reference to input string
+    /// (empty if FSM runs through items of other type, see "FSM::proceed_iter")
     pub string: &'a String,
-    /// Current character position in input string
+    /// Current character (or item) position in input
     pub index: usize,
-    /// Current character in input string
-    pub character: char
+    /// Current character (or item) in input.
+    /// None before first item and after last one
+    /// (for entry effects of initial state, exit effects of last state and post-effects)
+    pub character: Option<Input>
+}
+
+/// Value returned by effector to control run of FSM
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Control<State, Error = ()> {
+    /// Run goes on as usual
+    Continue,
+    /// Run is finished successfully (rest of input and effects are skipped)
+    Stop,
+    /// Run is aborted with "FSMError::Effect"
+    Error(Error),
+    /// Run goes on from given state (instead of target of transition);
+    /// the rest of effects of current step are skipped
+    /// and entry effect of given state isn't dispatched.
+    /// Run fails with "FSMError::StateDoesNotExist" if state doesn't belong to machine
+    GoTo(State)
 }
 
 /// Generic type for executor of side effects 
 /// applied to some persistent data
-pub trait Effector<Effect> 
-    where Effect: Copy
+pub trait Effector<Effect, State, Error = (), Input = char> 
+    where Effect: Clone
 {
     /// Applies side effect to mutate some data
+    /// and tells how run should go on
     /// - effect: side effect,
     /// - input_data: additional dependencies for effects
-    fn dispatch(&mut self, effect: Effect, input_data: StreamData);
+    fn dispatch(&mut self, effect: Effect, input_data: StreamData<Input>) -> Control<State, Error>;
+}
+
+/// Listener of FSM run that is notified about visited states
+/// and transitions regardless of effects (e.g. for metrics and logging).
+/// Every hook does nothing by default
+pub trait Observer<State, Input = char> 
+    where State: Copy
+{
+    /// Called when run enters state (including initial state)
+    /// - state: entered state
+    fn on_enter(&mut self, _state: State) {}
+
+    /// Called when run leaves state
+    /// - state: left state
+    fn on_exit(&mut self, _state: State) {}
+
+    /// Called for every accepted character
+    /// - from: previous state,
+    /// - to: next state,
+    /// - input_data: accepted character and its position
+    fn on_transition(&mut self, _from: State, _to: State, _input_data: &StreamData<Input>) {}
+
+    /// Called when no transition accepts character
+    /// - state: current state,
+    /// - input_data: rejected character and its position
+    fn on_reject(&mut self, _state: State, _input_data: &StreamData<Input>) {}
+
+    /// Called after the whole input is accepted (and post-effect is dispatched)
+    /// - state: final state of run
+    fn on_finish(&mut self, _state: State) {}
 }
@@ -0,0 +1,105 @@
+use core::hash::Hash;
+use core::fmt::Debug;
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::types::Predicate;
+
+/// Names of states, conditions and effects of FSM.
+/// Predicates and effects can't be printed or parsed by themselves,
+/// so every one of them that should be written out (into SCXML document
+/// or generated code) or read back has to be registered here
+/// (conditions are looked up by names that transitions give them, see "Transition::naming")
+pub struct Vocabulary<State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    /// Registered states (in order of registration)
+    states: Vec<(State, String)>,
+    /// Registered conditions of transitions
+    conditions: Vec<(&'static str, Predicate)>,
+    /// Registered effects
+    effects: Vec<(Effect, String)>
+}
+
+impl<State, Effect> Vocabulary<State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    /// Creates empty vocabulary
+    pub fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            conditions: Vec::new(),
+            effects: Vec::new()
+        }
+    }
+
+    /// Registers name of state
+    /// - state: state of FSM,
+    /// - name: its name
+    pub fn state(mut self, state: State, name: &str) -> Self {
+        self.states.push((state, String::from(name)));
+        self
+    }
+
+    /// Registers condition under name.
+    /// Transitions refer to condition by the same name (see "Transition::naming")
+    /// - name: its name,
+    /// - condition: predicate of transition
+    pub fn condition(mut self, name: &'static str, condition: Predicate) -> Self {
+        self.conditions.push((name, condition));
+        self
+    }
+
+    /// Registers name of effect
+    /// - effect: side effect,
+    /// - name: its name
+    pub fn effect(mut self, effect: Effect, name: &str) -> Self {
+        self.effects.push((effect, String::from(name)));
+        self
+    }
+
+    /// Iterates over registered states with their names (in order of registration)
+    pub(crate) fn states(&self) -> impl Iterator<Item = (State, &str)> + '_ {
+        self.states.iter().map(|(state, name)| (*state, name.as_str()))
+    }
+
+    pub(crate) fn state_name(&self, state: State) -> Option<&str> {
+        self.states.iter()
+            .find(|(registered, _)| *registered == state)
+            .map(|(_, name)| name.as_str())
+    }
+
+    pub(crate) fn state_by_name(&self, name: &str) -> Option<State> {
+        self.states.iter()
+            .find(|(_, registered)| registered == name)
+            .map(|(state, _)| *state)
+    }
+
+    pub(crate) fn condition_by_name(&self, name: &str) -> Option<(&'static str, Predicate)> {
+        self.conditions.iter()
+            .find(|(registered, _)| *registered == name)
+            .copied()
+    }
+
+    pub(crate) fn effect_name(&self, effect: &Effect) -> Option<&str> {
+        self.effects.iter()
+            .find(|(registered, _)| registered == effect)
+            .map(|(_, name)| name.as_str())
+    }
+
+    pub(crate) fn effect_by_name(&self, name: &str) -> Option<Effect> {
+        self.effects.iter()
+            .find(|(_, registered)| registered == name)
+            .map(|(effect, _)| effect.clone())
+    }
+}
+
+impl<State, Effect> Default for Vocabulary<State, Effect>
+    where State: Eq + PartialEq + Copy + Hash + Debug,
+          Effect: Clone + PartialEq
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}